@@ -114,6 +114,11 @@ pub fn verify(
 /// Create an attestation.
 ///
 /// Signs the attestation with the signer's private key.
+///
+/// # Panics
+///
+/// Panics if the signer fails to sign the attestation. See [`try_create`] for a non-panicking
+/// version.
 pub fn create<S: SignerSync>(
     domain: &Eip712Domain,
     signer: &S,
@@ -121,24 +126,36 @@ pub fn create<S: SignerSync>(
     request: &str,
     response: &str,
 ) -> Attestation {
+    try_create(domain, signer, deployment, request, response).expect("failed to sign attestation")
+}
+
+/// Create an attestation.
+///
+/// Signs the attestation with the signer's private key. Returns an error if the signer fails to
+/// sign the attestation.
+pub fn try_create<S: SignerSync>(
+    domain: &Eip712Domain,
+    signer: &S,
+    deployment: &DeploymentId,
+    request: &str,
+    response: &str,
+) -> Result<Attestation, alloy::signers::Error> {
     let msg = Receipt {
         requestCID: keccak256(request),
         responseCID: keccak256(response),
         subgraphDeploymentID: deployment.into(),
     };
 
-    let signature = signer
-        .sign_typed_data_sync(&msg, domain)
-        .expect("failed to sign attestation");
+    let signature = signer.sign_typed_data_sync(&msg, domain)?;
 
-    Attestation {
+    Ok(Attestation {
         request_cid: msg.requestCID,
         response_cid: msg.responseCID,
         deployment: deployment.into(),
         r: signature.r().into(),
         s: signature.s().into(),
         v: signature.recid().into(),
-    }
+    })
 }
 
 /// Recover the signer's allocation address from the attestation.
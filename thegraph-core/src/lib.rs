@@ -25,7 +25,10 @@
 //! - `fake`: Enables the [`fake`] crate integration for generating random test data.
 //! - `serde`: Enables [`serde`] serialization and deserialization support for types in this crate.
 //! - `signed-message`: Enables the `signed_message` module, which provides types and functions for
-//!   EIP-712 message signing and verification.
+//!   EIP-712 (and EIP-191) message signing and verification. Combined with the `alloy-dyn-abi`
+//!   feature, it also supports signing and verifying runtime-defined EIP-712 typed data.
+//! - `tally`: Enables the `tally` module, which provides the Graph Tally (TAP) receipt and Receipt
+//!   Aggregate Voucher types, and the receipts aggregation function.
 //!
 //! Additionally, this crate re-exports other features from the `alloy` crate as described above.
 
@@ -62,6 +65,9 @@ mod proof_of_indexing;
 #[cfg_attr(docsrs, doc(cfg(feature = "signed-message")))]
 pub mod signed_message;
 mod subgraph_id;
+#[cfg(feature = "tally")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tally")))]
+pub mod tally;
 
 // Export macros
 #[doc(inline)]
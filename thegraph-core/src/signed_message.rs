@@ -1,4 +1,4 @@
-//! EIP-712 message signing and verification.
+//! EIP-712 (and EIP-191) message signing and verification.
 //!
 //! This module provides the [`SignedMessage`] struct for signing and verifying messages according
 //! to the [EIP-712] standard.
@@ -13,6 +13,39 @@
 //! To use a Rust struct as a message, it must implement the [`ToSolStruct`] trait.
 //! Refer to the example below for more details.
 //!
+//! ## Multi-signer authorization
+//!
+//! Messages are often accepted from any signer in a set, e.g., all the authorized gateway
+//! signers or an indexer's operators. The [`verify_authorized`] function checks the recovered
+//! signer's address against a [`SignerAuthorization`] lookup and returns the matching identity.
+//! Sets and maps of addresses implement this trait, and the [`SignerAllowlist`] type provides an
+//! allowlist that can be hot-swapped at runtime behind an [`Arc`](std::sync::Arc).
+//!
+//! ## EIP-191 signed messages
+//!
+//! Some flows still rely on [EIP-191] `personal_sign` messages. The following functions provide
+//! the same ergonomics for any message that can be viewed as a byte slice:
+//!
+//! - [`sign_personal_message`]: Signs a message using the EIP-191 standard.
+//! - [`recover_personal_message_signer_address`]: Recovers the signer's address from an EIP-191
+//!   signed message.
+//! - [`verify_personal_message`]: Convenience wrapper over
+//!   [`recover_personal_message_signer_address`] to verify the signer's address.
+//!
+//! Use [`SchemeSignedMessage`] to record which [`SignatureScheme`] was used to sign a message.
+//!
+//! ## Runtime-defined typed data
+//!
+//! When the message schema is only known at runtime, e.g., an [`eth_signTypedData_v4`] JSON
+//! document coming from a browser wallet, the `alloy-dyn-abi` feature enables the following
+//! functions over a [`TypedDataMessage`]:
+//!
+//! - [`sign_typed_data`]: Signs a typed data message using the EIP-712 standard.
+//! - [`recover_typed_data_signer_address`]: Recovers the signer's address from a signed typed
+//!   data message.
+//! - [`verify_typed_data`]: Convenience wrapper over [`recover_typed_data_signer_address`] to
+//!   verify the signer's address.
+//!
 //! ## Example
 //! ```rust
 //! # use thegraph_core::alloy::{
@@ -72,14 +105,34 @@
 //! ```
 //!
 //! [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+//! [EIP-191]: https://eips.ethereum.org/EIPS/eip-191 "EIP-191"
+//! [`eth_signTypedData_v4`]: https://eips.ethereum.org/EIPS/eip-712#specification-of-the-eth_signtypeddata-json-rpc
 
+mod authorization;
 mod message;
+mod personal;
 mod signing;
+#[cfg(feature = "alloy-dyn-abi")]
+mod typed_data;
 
-pub use message::{MessageHash, SignatureBytes, SignedMessage, ToSolStruct};
+pub use authorization::{
+    AuthorizationError, SignerAllowlist, SignerAuthorization, UnauthorizedSigner, authorize,
+    verify_authorized,
+};
+pub use message::{
+    MessageHash, SchemeSignedMessage, SignatureBytes, SignatureScheme, SignedMessage, ToSolStruct,
+};
+pub use personal::{
+    recover_personal_message_signer_address, sign_personal_message, verify_personal_message,
+};
 pub use signing::{
     RecoverSignerError, SigningError, VerificationError, recover_signer_address, sign, verify,
 };
+#[cfg(feature = "alloy-dyn-abi")]
+#[cfg_attr(docsrs, doc(cfg(feature = "alloy-dyn-abi")))]
+pub use typed_data::{
+    TypedDataMessage, recover_typed_data_signer_address, sign_typed_data, verify_typed_data,
+};
 
 #[cfg(test)]
 mod tests {
@@ -89,7 +142,11 @@ mod tests {
         sol_types::{Eip712Domain, eip712_domain},
     };
 
-    use super::{message::SignedMessage, signing, signing::VerificationError};
+    use super::{
+        message::{SchemeSignedMessage, SignatureScheme, SignedMessage},
+        personal, signing,
+        signing::VerificationError,
+    };
 
     /// Test EIP712 domain separator
     const EIP712_DOMAIN: Eip712Domain = eip712_domain! {
@@ -244,4 +301,110 @@ mod tests {
             panic!("unexpected error: {:?}", error);
         }
     }
+
+    #[test]
+    fn sign_and_verify_personal_message() {
+        //* Given
+        let signer = wallet();
+        let signer_address = signer.address();
+
+        let message = b"Hello, world!";
+
+        //* When
+        // Sign the message and verify the signer's address
+        let signed_message = personal::sign_personal_message(&signer, message).unwrap();
+        let result = personal::verify_personal_message(&signed_message, &signer_address);
+
+        //* Then
+        // The signature should be valid
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn recover_signer_from_personal_message_signed_with_eip191_prefix() {
+        //* Given
+        let signer = wallet();
+
+        let message = "Hello, world!";
+
+        // Sign the message
+        let signed_message = personal::sign_personal_message(&signer, message).unwrap();
+
+        //* When
+        // Recover the signer's address
+        let result = personal::recover_personal_message_signer_address(&signed_message);
+
+        //* Then
+        // The signer should be the wallet's address
+        assert_eq!(result.expect("recover_signer failed"), signer.address());
+
+        // The signature should be over the EIP-191 prefixed message hash
+        let prehash = alloy::primitives::eip191_hash_message(message);
+        assert_eq!(
+            signed_message
+                .signature
+                .recover_address_from_prehash(&prehash)
+                .expect("recover_signer failed"),
+            signer.address()
+        );
+    }
+
+    #[test]
+    fn personal_message_verification_should_fail_with_invalid_signer() {
+        //* Given
+        let signer = wallet();
+
+        // Sign the message
+        let signed_message = personal::sign_personal_message(&signer, b"Hello, world!").unwrap();
+
+        // Create a different signer
+        let different_signer = wallet();
+        let different_signer_address = different_signer.address();
+
+        //* When
+        // Verify the signed message
+        let result = personal::verify_personal_message(&signed_message, &different_signer_address);
+
+        //* Then
+        // The signature should be invalid
+        let error = result.expect_err("verify_personal_message should fail");
+        if let VerificationError::InvalidSigner { expected, received } = error {
+            assert_eq!(expected, different_signer_address);
+            assert_eq!(received, signer.address());
+        } else {
+            panic!("unexpected error: {:?}", error);
+        }
+    }
+
+    #[test]
+    fn scheme_signed_message_records_the_signature_scheme() {
+        //* Given
+        let signer = wallet();
+        let domain = EIP712_DOMAIN;
+
+        let message = Message {
+            data: keccak256(b"Hello, world!"),
+        };
+
+        //* When
+        let eip712 =
+            SchemeSignedMessage::Eip712(signing::sign(&signer, &domain, message.clone()).unwrap());
+        let eip191 = SchemeSignedMessage::Eip191(
+            personal::sign_personal_message(&signer, message.data).unwrap(),
+        );
+
+        //* Then
+        assert_eq!(eip712.scheme(), SignatureScheme::Eip712);
+        assert_eq!(eip191.scheme(), SignatureScheme::Eip191);
+
+        // The signatures must differ, as the signed digests are different
+        assert_ne!(eip712.signature(), eip191.signature());
+
+        // Each message must be verifiable with its scheme's functions
+        assert!(signing::verify(&domain, eip712.as_signed_message(), &signer.address()).is_ok());
+        assert!(
+            personal::verify_personal_message(eip191.as_signed_message(), &signer.address())
+                .is_ok()
+        );
+    }
 }
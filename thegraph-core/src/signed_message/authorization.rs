@@ -0,0 +1,329 @@
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap, HashSet},
+    hash::BuildHasher,
+    sync::{Arc, PoisonError, RwLock},
+};
+
+use alloy::{
+    primitives::{Address, SignatureError},
+    sol_types::{Eip712Domain, SolStruct},
+};
+
+use super::{
+    message::{SignedMessage, ToSolStruct},
+    signing::{RecoverSignerError, recover_signer_address},
+};
+
+/// The recovered signer is not authorized to sign the message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, thiserror::Error)]
+#[error("signer `{signer}` is not authorized")]
+pub struct UnauthorizedSigner {
+    /// The recovered signer's address
+    pub signer: Address,
+}
+
+/// Errors that can occur when checking the signer's authorization of a message.
+#[derive(Debug, thiserror::Error)]
+pub enum AuthorizationError {
+    /// Errors in signature parsing or verification
+    #[error(transparent)]
+    SignatureError(#[from] SignatureError),
+
+    /// The signer's address is not authorized
+    #[error(transparent)]
+    UnauthorizedSigner(#[from] UnauthorizedSigner),
+}
+
+/// A lookup of the signers authorized to sign messages.
+///
+/// Implementors map an authorized signer's address to an _identity_, e.g., the indexer an
+/// operator acts on behalf of. Sets of addresses resolve to the signer's address itself.
+pub trait SignerAuthorization {
+    /// The identity associated with an authorized signer
+    type Identity;
+
+    /// Get the identity associated with the signer's address.
+    ///
+    /// Returns `None` if the signer is not authorized.
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity>;
+}
+
+impl<T: SignerAuthorization + ?Sized> SignerAuthorization for &T {
+    type Identity = T::Identity;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        (**self).authorized_identity(signer)
+    }
+}
+
+impl<T: SignerAuthorization + ?Sized> SignerAuthorization for Arc<T> {
+    type Identity = T::Identity;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        (**self).authorized_identity(signer)
+    }
+}
+
+impl SignerAuthorization for [Address] {
+    type Identity = Address;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.contains(signer).then_some(*signer)
+    }
+}
+
+impl<S: BuildHasher> SignerAuthorization for HashSet<Address, S> {
+    type Identity = Address;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.get(signer).copied()
+    }
+}
+
+impl SignerAuthorization for BTreeSet<Address> {
+    type Identity = Address;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.get(signer).copied()
+    }
+}
+
+impl<I: Clone, S: BuildHasher> SignerAuthorization for HashMap<Address, I, S> {
+    type Identity = I;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.get(signer).cloned()
+    }
+}
+
+impl<I: Clone> SignerAuthorization for BTreeMap<Address, I> {
+    type Identity = I;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.get(signer).cloned()
+    }
+}
+
+/// A hot-swappable allowlist of authorized signers.
+///
+/// The allowlist is meant to be shared behind an [`Arc`]. The set of authorized signers can be
+/// atomically replaced at runtime via [`SignerAllowlist::replace`], e.g., when the set of gateway
+/// signers or an indexer's operators changes, without interrupting in-flight verifications.
+#[derive(Debug)]
+pub struct SignerAllowlist<I = Address> {
+    signers: RwLock<Arc<HashMap<Address, I>>>,
+}
+
+impl<I> SignerAllowlist<I> {
+    /// Create a new allowlist with the given signers and their identities.
+    pub fn new(signers: impl IntoIterator<Item = (Address, I)>) -> Self {
+        Self {
+            signers: RwLock::new(Arc::new(signers.into_iter().collect())),
+        }
+    }
+
+    /// Replace the authorized signers.
+    pub fn replace(&self, signers: impl IntoIterator<Item = (Address, I)>) {
+        let signers = Arc::new(signers.into_iter().collect());
+        *self.signers.write().unwrap_or_else(PoisonError::into_inner) = signers;
+    }
+
+    /// Get a snapshot of the currently authorized signers.
+    pub fn snapshot(&self) -> Arc<HashMap<Address, I>> {
+        self.signers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clone()
+    }
+}
+
+impl<I> Default for SignerAllowlist<I> {
+    fn default() -> Self {
+        Self::new([])
+    }
+}
+
+impl<I> FromIterator<(Address, I)> for SignerAllowlist<I> {
+    fn from_iter<T: IntoIterator<Item = (Address, I)>>(iter: T) -> Self {
+        Self::new(iter)
+    }
+}
+
+impl FromIterator<Address> for SignerAllowlist<Address> {
+    fn from_iter<T: IntoIterator<Item = Address>>(iter: T) -> Self {
+        Self::new(iter.into_iter().map(|signer| (signer, signer)))
+    }
+}
+
+impl<I: Clone> SignerAuthorization for SignerAllowlist<I> {
+    type Identity = I;
+
+    fn authorized_identity(&self, signer: &Address) -> Option<Self::Identity> {
+        self.signers
+            .read()
+            .unwrap_or_else(PoisonError::into_inner)
+            .get(signer)
+            .cloned()
+    }
+}
+
+/// Check the signer's address against a [`SignerAuthorization`] lookup.
+///
+/// Returns the signer's identity if authorized. Otherwise, returns an [`UnauthorizedSigner`]
+/// error. Use this function to check the signer recovered via any of the module's recovery
+/// functions, e.g., [`recover_personal_message_signer_address`].
+///
+/// [`recover_personal_message_signer_address`]: super::recover_personal_message_signer_address
+pub fn authorize<A>(authorization: &A, signer: &Address) -> Result<A::Identity, UnauthorizedSigner>
+where
+    A: SignerAuthorization + ?Sized,
+{
+    authorization
+        .authorized_identity(signer)
+        .ok_or(UnauthorizedSigner { signer: *signer })
+}
+
+/// Verify that the signer of an [EIP-712] signed message is authorized
+///
+/// Returns the identity of the signer if the signer's address retrieved from the signature is
+/// authorized. Otherwise, returns an [`AuthorizationError`].
+///
+/// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+pub fn verify_authorized<M, MSol, A>(
+    domain: &Eip712Domain,
+    signed_message: &SignedMessage<M>,
+    authorization: &A,
+) -> Result<A::Identity, AuthorizationError>
+where
+    M: ToSolStruct<MSol>,
+    MSol: SolStruct,
+    A: SignerAuthorization + ?Sized,
+{
+    let recovered_address =
+        recover_signer_address(domain, signed_message).map_err(|RecoverSignerError(err)| err)?;
+    Ok(authorize(authorization, &recovered_address)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::{HashMap, HashSet},
+        sync::Arc,
+    };
+
+    use alloy::{
+        primitives::{address, b256, keccak256},
+        signers::local::PrivateKeySigner,
+        sol_types::{Eip712Domain, eip712_domain},
+    };
+
+    use super::{AuthorizationError, SignerAllowlist, UnauthorizedSigner, verify_authorized};
+    use crate::{IndexerId, indexer_id, signed_message::sign};
+
+    /// Test EIP712 domain separator
+    const EIP712_DOMAIN: Eip712Domain = eip712_domain! {
+        name: "Test domain",
+        version: "1",
+        chain_id: 1,
+        verifying_contract: address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"),
+        salt: b256!("66eb090e6dbb9668c7d32c0ee7ba5e8f08d84385804485d316dd5f5692273593")
+    };
+
+    const INDEXER: IndexerId = indexer_id!("0002b1b9cd2e9f0bd1a8b0c7e4bba4d68e1d5ee1");
+
+    alloy::sol! {
+        /// Test struct for EIP712 message
+        struct Message {
+            bytes32 data;
+        }
+    }
+
+    #[test]
+    fn verify_message_signed_by_any_signer_in_set() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let signers = HashSet::from([PrivateKeySigner::random().address(), signer.address()]);
+
+        let message = Message {
+            data: keccak256(b"Hello, world!"),
+        };
+        let signed_message = sign(&signer, &EIP712_DOMAIN, message).unwrap();
+
+        //* When
+        let result = verify_authorized(&EIP712_DOMAIN, &signed_message, &signers);
+
+        //* Then
+        assert_eq!(result.expect("verify_authorized failed"), signer.address());
+    }
+
+    #[test]
+    fn verify_message_returns_the_signer_identity() {
+        //* Given
+        let operator = PrivateKeySigner::random();
+        let operators = HashMap::from([(operator.address(), INDEXER)]);
+
+        let message = Message {
+            data: keccak256(b"Hello, world!"),
+        };
+        let signed_message = sign(&operator, &EIP712_DOMAIN, message).unwrap();
+
+        //* When
+        let result = verify_authorized(&EIP712_DOMAIN, &signed_message, &operators);
+
+        //* Then
+        assert_eq!(result.expect("verify_authorized failed"), INDEXER);
+    }
+
+    #[test]
+    fn verification_should_fail_with_unauthorized_signer() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let signers = [PrivateKeySigner::random().address()];
+
+        let message = Message {
+            data: keccak256(b"Hello, world!"),
+        };
+        let signed_message = sign(&signer, &EIP712_DOMAIN, message).unwrap();
+
+        //* When
+        let result = verify_authorized(&EIP712_DOMAIN, &signed_message, &signers[..]);
+
+        //* Then
+        let error = result.expect_err("verify_authorized should fail");
+        if let AuthorizationError::UnauthorizedSigner(UnauthorizedSigner { signer: received }) =
+            error
+        {
+            assert_eq!(received, signer.address());
+        } else {
+            panic!("unexpected error: {:?}", error);
+        }
+    }
+
+    #[test]
+    fn verify_message_against_hot_swapped_allowlist() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let allowlist = Arc::new(SignerAllowlist::from_iter([
+            PrivateKeySigner::random().address()
+        ]));
+
+        let message = Message {
+            data: keccak256(b"Hello, world!"),
+        };
+        let signed_message = sign(&signer, &EIP712_DOMAIN, message).unwrap();
+
+        // The signer is not authorized yet
+        assert!(verify_authorized(&EIP712_DOMAIN, &signed_message, &allowlist).is_err());
+
+        //* When
+        // Replace the allowlist contents from a different handle
+        let handle = Arc::clone(&allowlist);
+        handle.replace([(signer.address(), signer.address())]);
+
+        let result = verify_authorized(&EIP712_DOMAIN, &signed_message, &allowlist);
+
+        //* Then
+        assert_eq!(result.expect("verify_authorized failed"), signer.address());
+        assert_eq!(allowlist.snapshot().len(), 1);
+    }
+}
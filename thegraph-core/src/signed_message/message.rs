@@ -3,7 +3,8 @@ use alloy::{primitives::Signature, sol_types::SolStruct};
 /// EIP-712 signed message
 ///
 /// This struct contains a message and the ECDSA signature of the message according to the
-/// EIP-712 standard.
+/// EIP-712 standard. It is also used to hold [EIP-191](https://eips.ethereum.org/EIPS/eip-191)
+/// signed messages, see [`SchemeSignedMessage`] to keep track of the scheme used.
 ///
 /// For the message to be signed, it must either:
 /// - To be a _Solidity struct_, i.e., implement the `SolStruct` trait.
@@ -42,6 +43,65 @@ impl<M> SignedMessage<M> {
     }
 }
 
+/// The signature scheme used to sign a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SignatureScheme {
+    /// [EIP-712](https://eips.ethereum.org/EIPS/eip-712) typed structured data signing
+    Eip712,
+    /// [EIP-191](https://eips.ethereum.org/EIPS/eip-191) `personal_sign` message signing
+    Eip191,
+}
+
+/// A signed message that records the signature scheme used to sign it.
+///
+/// Each variant wraps a [`SignedMessage`]. To recover or verify the signer's address, match on
+/// the variant and use the corresponding scheme functions, e.g., [`verify`] for EIP-712 and
+/// [`verify_personal_message`] for EIP-191 signed messages.
+///
+/// [`verify`]: super::verify
+/// [`verify_personal_message`]: super::verify_personal_message
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemeSignedMessage<M> {
+    /// An EIP-712 signed message
+    Eip712(SignedMessage<M>),
+    /// An EIP-191 signed message
+    Eip191(SignedMessage<M>),
+}
+
+impl<M> SchemeSignedMessage<M> {
+    /// Get the signature scheme used to sign the message.
+    pub fn scheme(&self) -> SignatureScheme {
+        match self {
+            Self::Eip712(_) => SignatureScheme::Eip712,
+            Self::Eip191(_) => SignatureScheme::Eip191,
+        }
+    }
+
+    /// Get the message payload.
+    pub fn message(&self) -> &M {
+        &self.as_signed_message().message
+    }
+
+    /// Get the ECDSA message signature.
+    pub fn signature(&self) -> &Signature {
+        &self.as_signed_message().signature
+    }
+
+    /// Get the inner [`SignedMessage`] reference.
+    pub fn as_signed_message(&self) -> &SignedMessage<M> {
+        match self {
+            Self::Eip712(signed_message) | Self::Eip191(signed_message) => signed_message,
+        }
+    }
+
+    /// Return the inner [`SignedMessage`], discarding the signature scheme.
+    pub fn into_signed_message(self) -> SignedMessage<M> {
+        match self {
+            Self::Eip712(signed_message) | Self::Eip191(signed_message) => signed_message,
+        }
+    }
+}
+
 /// The EIP-712 ECDSA signature bytes.
 ///
 /// See: [`SignedMessage::signature_bytes`]
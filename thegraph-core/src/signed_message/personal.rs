@@ -0,0 +1,66 @@
+use alloy::{primitives::Address, signers::SignerSync};
+
+use super::{
+    message::SignedMessage,
+    signing::{RecoverSignerError, SigningError, VerificationError, signing_error},
+};
+
+/// Signs a message using the [EIP-191] `personal_sign` standard
+///
+/// The message bytes are prefixed with `"\x19Ethereum Signed Message:\n" + len(message)` before
+/// hashing and signing. Returns a [`SignedMessage`] containing the message and the ECDSA
+/// signature of the message.
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191 "EIP-191"
+pub fn sign_personal_message<S, M>(signer: &S, message: M) -> Result<SignedMessage<M>, SigningError>
+where
+    S: SignerSync,
+    M: AsRef<[u8]>,
+{
+    let signature = signer
+        .sign_message_sync(message.as_ref())
+        .map_err(signing_error)?;
+    Ok(SignedMessage { message, signature })
+}
+
+/// Recover the signer's address of an [EIP-191] signed message
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191 "EIP-191"
+pub fn recover_personal_message_signer_address<M>(
+    signed_message: &SignedMessage<M>,
+) -> Result<Address, RecoverSignerError>
+where
+    M: AsRef<[u8]>,
+{
+    let recovered_address = signed_message
+        .signature
+        .recover_address_from_msg(signed_message.message.as_ref())?;
+    Ok(recovered_address)
+}
+
+/// Verify the signer's address of an [EIP-191] signed message
+///
+/// Returns `Ok(())` if the signer's address retrieved from the signature matches the expected
+/// address. Otherwise, returns a [`VerificationError`] with details about the mismatch.
+///
+/// [EIP-191]: https://eips.ethereum.org/EIPS/eip-191 "EIP-191"
+pub fn verify_personal_message<M>(
+    signed_message: &SignedMessage<M>,
+    expected_address: &Address,
+) -> Result<(), VerificationError>
+where
+    M: AsRef<[u8]>,
+{
+    let recovered_address = signed_message
+        .signature
+        .recover_address_from_msg(signed_message.message.as_ref())?;
+
+    if recovered_address != *expected_address {
+        Err(VerificationError::InvalidSigner {
+            expected: expected_address.to_owned(),
+            received: recovered_address,
+        })
+    } else {
+        Ok(())
+    }
+}
@@ -28,7 +28,7 @@ pub enum SigningError {
 /// Errors that can occur when recovering the signer's address of a message.
 #[derive(Debug, thiserror::Error)]
 #[error(transparent)]
-pub struct RecoverSignerError(#[from] SignatureError);
+pub struct RecoverSignerError(#[from] pub(super) SignatureError);
 
 /// Errors that can occur when verifying the signer's address of a message.
 #[derive(Debug, thiserror::Error)]
@@ -65,26 +65,34 @@ where
     let message_sol = message.to_sol_struct();
     let signature = signer
         .sign_typed_data_sync(&message_sol, domain)
-        .map_err(|err| match err {
-            SignerError::UnsupportedOperation(err) => SigningError::UnsupportedOperation(err),
-            SignerError::TransactionChainIdMismatch { .. } => {
-                unreachable!("sign_typed_data_sync should not return TransactionChainIdMismatch")
-            }
-            SignerError::DynAbiError(_) => {
-                unreachable!("sign_typed_data_sync should not return DynAbiError")
-            }
-            SignerError::Ecdsa(err) => SigningError::Ecdsa(err),
-            SignerError::HexError(_) => {
-                unreachable!("sign_typed_data_sync should not return HexError")
-            }
-            SignerError::SignatureError(_) => {
-                unreachable!("sign_typed_data_sync should not return SignatureError")
-            }
-            SignerError::Other(err) => SigningError::Other(err),
-        })?;
+        .map_err(signing_error)?;
     Ok(SignedMessage { message, signature })
 }
 
+/// Map a signer error into a [`SigningError`].
+///
+/// Signing a message can only fail because of the signer, the remaining error variants are
+/// unreachable.
+pub(super) fn signing_error(err: SignerError) -> SigningError {
+    match err {
+        SignerError::UnsupportedOperation(err) => SigningError::UnsupportedOperation(err),
+        SignerError::TransactionChainIdMismatch { .. } => {
+            unreachable!("message signing should not return TransactionChainIdMismatch")
+        }
+        SignerError::DynAbiError(_) => {
+            unreachable!("message signing should not return DynAbiError")
+        }
+        SignerError::Ecdsa(err) => SigningError::Ecdsa(err),
+        SignerError::HexError(_) => {
+            unreachable!("message signing should not return HexError")
+        }
+        SignerError::SignatureError(_) => {
+            unreachable!("message signing should not return SignatureError")
+        }
+        SignerError::Other(err) => SigningError::Other(err),
+    }
+}
+
 /// Recover the signer's address  an [EIP-712] signed message
 ///
 /// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
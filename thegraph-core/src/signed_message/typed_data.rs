@@ -0,0 +1,304 @@
+use alloy::{
+    dyn_abi::{Error as DynAbiError, TypedData},
+    primitives::{Address, B256},
+    signers::SignerSync,
+};
+
+use super::{
+    message::SignedMessage,
+    signing::{RecoverSignerError, SigningError, VerificationError, signing_error},
+};
+
+/// An EIP-712 typed data message whose schema is only known at runtime.
+///
+/// This is a wrapper around an [`eth_signTypedData_v4`] JSON document, i.e., a [`TypedData`]
+/// value. Its EIP-712 signing hash is computed when the message is created, so a
+/// [`TypedDataMessage`] is guaranteed to be encodable according to its types definition.
+///
+/// [`eth_signTypedData_v4`]: https://eips.ethereum.org/EIPS/eip-712#specification-of-the-eth_signtypeddata-json-rpc
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TypedDataMessage {
+    typed_data: TypedData,
+    signing_hash: B256,
+}
+
+impl TypedDataMessage {
+    /// Create a new [`TypedDataMessage`].
+    ///
+    /// Returns an error if the message cannot be encoded according to the typed data types
+    /// definition, e.g., the primary type is not defined or a field value does not match its type.
+    pub fn new(typed_data: TypedData) -> Result<Self, DynAbiError> {
+        let signing_hash = typed_data.eip712_signing_hash()?;
+        Ok(Self {
+            typed_data,
+            signing_hash,
+        })
+    }
+
+    /// Get the typed data document.
+    pub fn typed_data(&self) -> &TypedData {
+        &self.typed_data
+    }
+
+    /// Get the [EIP-712 signing hash](https://eips.ethereum.org/EIPS/eip-712#specification-of-the-eth_signtypeddata-json-rpc)
+    /// of the typed data document.
+    pub fn signing_hash(&self) -> B256 {
+        self.signing_hash
+    }
+
+    /// Return the internal typed data document.
+    pub fn into_inner(self) -> TypedData {
+        self.typed_data
+    }
+}
+
+impl TryFrom<TypedData> for TypedDataMessage {
+    type Error = DynAbiError;
+
+    fn try_from(typed_data: TypedData) -> Result<Self, Self::Error> {
+        Self::new(typed_data)
+    }
+}
+
+/// Signs a runtime-defined typed data message using the [EIP-712] standard
+///
+/// The EIP-712 domain is part of the typed data document. Returns a [`SignedMessage`] containing
+/// the message and the ECDSA signature of the message.
+///
+/// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+pub fn sign_typed_data<S>(
+    signer: &S,
+    message: TypedDataMessage,
+) -> Result<SignedMessage<TypedDataMessage>, SigningError>
+where
+    S: SignerSync,
+{
+    let signature = signer
+        .sign_hash_sync(&message.signing_hash)
+        .map_err(signing_error)?;
+    Ok(SignedMessage { message, signature })
+}
+
+/// Recover the signer's address of an [EIP-712] signed typed data message
+///
+/// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+pub fn recover_typed_data_signer_address(
+    signed_message: &SignedMessage<TypedDataMessage>,
+) -> Result<Address, RecoverSignerError> {
+    let recovered_address = signed_message
+        .signature
+        .recover_address_from_prehash(&signed_message.message.signing_hash)?;
+    Ok(recovered_address)
+}
+
+/// Verify the signer's address of an [EIP-712] signed typed data message
+///
+/// Returns `Ok(())` if the signer's address retrieved from the signature matches the expected
+/// address. Otherwise, returns a [`VerificationError`] with details about the mismatch.
+///
+/// [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+pub fn verify_typed_data(
+    signed_message: &SignedMessage<TypedDataMessage>,
+    expected_address: &Address,
+) -> Result<(), VerificationError> {
+    let recovered_address = signed_message
+        .signature
+        .recover_address_from_prehash(&signed_message.message.signing_hash)?;
+
+    if recovered_address != *expected_address {
+        Err(VerificationError::InvalidSigner {
+            expected: expected_address.to_owned(),
+            received: recovered_address,
+        })
+    } else {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        dyn_abi::TypedData,
+        primitives::{Signature, address, b256, keccak256},
+        signers::local::PrivateKeySigner,
+        sol_types::{Eip712Domain, eip712_domain},
+    };
+
+    use super::{
+        TypedDataMessage, recover_typed_data_signer_address, sign_typed_data, verify_typed_data,
+    };
+    use crate::signed_message::{SignedMessage, VerificationError, sign, verify};
+
+    /// The `Mail` example message from the EIP-712 specification
+    const MAIL_TYPED_DATA: &str = r#"{
+        "types": {
+            "EIP712Domain": [
+                { "name": "name", "type": "string" },
+                { "name": "version", "type": "string" },
+                { "name": "chainId", "type": "uint256" },
+                { "name": "verifyingContract", "type": "address" }
+            ],
+            "Person": [
+                { "name": "name", "type": "string" },
+                { "name": "wallet", "type": "address" }
+            ],
+            "Mail": [
+                { "name": "from", "type": "Person" },
+                { "name": "to", "type": "Person" },
+                { "name": "contents", "type": "string" }
+            ]
+        },
+        "primaryType": "Mail",
+        "domain": {
+            "name": "Ether Mail",
+            "version": "1",
+            "chainId": 1,
+            "verifyingContract": "0xCcCCccccCCCCcCCCCCCcCcCccCcCCCcCcccccccC"
+        },
+        "message": {
+            "from": { "name": "Cow", "wallet": "0xCD2a3d9F938E13CD947Ec05AbC7FE734Df8DD826" },
+            "to": { "name": "Bob", "wallet": "0xbBbBBBBbbBBBbbbBbbBbbbbBBbBbbbbBbBbbBBbB" },
+            "contents": "Hello, Bob!"
+        }
+    }"#;
+
+    /// Test EIP712 domain separator
+    const EIP712_DOMAIN: Eip712Domain = eip712_domain! {
+        name: "Test domain",
+        version: "1",
+        chain_id: 1,
+        verifying_contract: address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"),
+        salt: b256!("66eb090e6dbb9668c7d32c0ee7ba5e8f08d84385804485d316dd5f5692273593")
+    };
+
+    alloy::sol! {
+        /// Test struct for EIP712 message
+        struct Message {
+            bytes32 data;
+        }
+    }
+
+    /// Test utility method parsing a typed data JSON document
+    fn typed_data(json: &str) -> TypedDataMessage {
+        let typed_data: TypedData = serde_json::from_str(json).expect("invalid typed data JSON");
+        TypedDataMessage::new(typed_data).expect("invalid typed data")
+    }
+
+    #[test]
+    fn recover_signer_from_eip712_reference_message() {
+        //* Given
+        let message = typed_data(MAIL_TYPED_DATA);
+
+        // The `Mail` message signature, by the `keccak256("cow")` private key, as listed in the
+        // EIP-712 specification
+        let signed_message = SignedMessage {
+            message,
+            signature: Signature::from_scalars_and_parity(
+                b256!("4355c47d63924e8a72e509b65029052eb6c299d53a04e167c5775fd466751c9d"),
+                b256!("07299936d304c153f6443dfa05f40ff007d72911b6f72307f996231605b91562"),
+                true,
+            ),
+        };
+
+        //* When
+        let result = recover_typed_data_signer_address(&signed_message);
+
+        //* Then
+        assert_eq!(
+            signed_message.message.signing_hash(),
+            b256!("be609aee343fb3c4b28e1df9e632fca64fcfaede20f02e86244efddf30957bd2")
+        );
+        assert_eq!(
+            result.expect("recover_signer failed"),
+            address!("cd2a3d9f938e13cd947ec05abc7fe734df8dd826")
+        );
+    }
+
+    #[test]
+    fn typed_data_signature_matches_static_struct_signature() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+
+        let data = keccak256(b"Hello, world!");
+        let message = typed_data(&format!(
+            r#"{{
+                "types": {{
+                    "EIP712Domain": [
+                        {{ "name": "name", "type": "string" }},
+                        {{ "name": "version", "type": "string" }},
+                        {{ "name": "chainId", "type": "uint256" }},
+                        {{ "name": "verifyingContract", "type": "address" }},
+                        {{ "name": "salt", "type": "bytes32" }}
+                    ],
+                    "Message": [{{ "name": "data", "type": "bytes32" }}]
+                }},
+                "primaryType": "Message",
+                "domain": {{
+                    "name": "Test domain",
+                    "version": "1",
+                    "chainId": 1,
+                    "verifyingContract": "0xa83682bbe91c0d2d48a13fd751b2da8e989fe421",
+                    "salt": "0x66eb090e6dbb9668c7d32c0ee7ba5e8f08d84385804485d316dd5f5692273593"
+                }},
+                "message": {{ "data": "{data}" }}
+            }}"#
+        ));
+
+        //* When
+        let dynamic_signed = sign_typed_data(&signer, message).expect("sign_typed_data failed");
+        let static_signed = sign(&signer, &EIP712_DOMAIN, Message { data }).expect("sign failed");
+
+        //* Then
+        // Both signatures must be equivalent, and verifiable by the counterpart function
+        assert_eq!(dynamic_signed.signature, static_signed.signature);
+        assert!(verify_typed_data(&dynamic_signed, &signer.address()).is_ok());
+        assert!(
+            verify(
+                &EIP712_DOMAIN,
+                &SignedMessage {
+                    message: Message { data },
+                    signature: dynamic_signed.signature,
+                },
+                &signer.address()
+            )
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn typed_data_verification_should_fail_with_invalid_signer() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let different_signer = PrivateKeySigner::random();
+
+        let signed_message =
+            sign_typed_data(&signer, typed_data(MAIL_TYPED_DATA)).expect("sign_typed_data failed");
+
+        //* When
+        let result = verify_typed_data(&signed_message, &different_signer.address());
+
+        //* Then
+        let error = result.expect_err("verify_typed_data should fail");
+        if let VerificationError::InvalidSigner { expected, received } = error {
+            assert_eq!(expected, different_signer.address());
+            assert_eq!(received, signer.address());
+        } else {
+            panic!("unexpected error: {:?}", error);
+        }
+    }
+
+    #[test]
+    fn typed_data_message_creation_should_fail_with_undefined_primary_type() {
+        //* Given
+        let typed_data: TypedData = serde_json::from_str(
+            &MAIL_TYPED_DATA.replace(r#""primaryType": "Mail""#, r#""primaryType": "Letter""#),
+        )
+        .expect("invalid typed data JSON");
+
+        //* When
+        let result = TypedDataMessage::new(typed_data);
+
+        //* Then
+        assert!(result.is_err());
+    }
+}
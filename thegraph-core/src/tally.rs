@@ -0,0 +1,164 @@
+//! Graph Tally (TAP) receipts and Receipt Aggregate Vouchers (RAVs).
+//!
+//! The _Graph Tally_ is the payment protocol used by _The Graph_ network's data services. For
+//! every paid query, the payer (e.g., a gateway) sends the service provider (e.g., an indexer) a
+//! [`Receipt`] signed according to the [EIP-712] standard. The service provider periodically
+//! requests the payer to aggregate the collected receipts into a [`ReceiptAggregateVoucher`]
+//! (RAV), which can be redeemed on-chain via the `GraphTallyCollector` contract.
+//!
+//! Receipts and RAVs are signed and verified using the [`signed_message`] module functions, with
+//! the domain returned by [`eip712_domain`]:
+//!
+//! - [`SignedReceipt`]: An EIP-712 signed [`Receipt`].
+//! - [`SignedRav`]: An EIP-712 signed [`ReceiptAggregateVoucher`].
+//! - [`aggregate_receipts`]: Folds a set of signed receipts, and the previous RAV, if any, into a
+//!   new RAV.
+//!
+//! To validate the receipts received by a service provider, see the composable checks in the
+//! [`checks`] module.
+//!
+//! The legacy TAP v1 messages, keyed by allocation ID, and the helpers to migrate them onto the
+//! collection-keyed messages are available in the [`v1`] module.
+//!
+//! ## Example
+//! ```rust
+//! # use thegraph_core::alloy::primitives::{Bytes, address};
+//! use thegraph_core::{
+//!     collection_id,
+//!     signed_message::{sign, verify},
+//!     tally::{Receipt, aggregate_receipts, eip712_domain},
+//! };
+//!
+//! // Create a signer instance
+//! let signer = thegraph_core::alloy::signers::local::PrivateKeySigner::random();
+//!
+//! // The Graph Tally EIP-712 domain separator
+//! let domain = eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+//!
+//! // Create and sign a receipt
+//! let receipt = Receipt {
+//!     collection_id: collection_id!(
+//!         "0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"
+//!     ),
+//!     payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+//!     data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+//!     service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+//!     timestamp_ns: 1_700_000_000_000_000_000,
+//!     nonce: 42,
+//!     value: 1_000,
+//! };
+//! let signed_receipt = sign(&signer, &domain, receipt).expect("failed to sign receipt");
+//! assert!(verify(&domain, &signed_receipt, &signer.address()).is_ok());
+//!
+//! // Aggregate the receipts into a RAV, accepting receipts signed by the signer only
+//! let rav = aggregate_receipts(&domain, &[signed_receipt], None, &[signer.address()][..])
+//!     .expect("failed to aggregate receipts");
+//! assert_eq!(rav.value_aggregate, 1_000);
+//! assert_eq!(rav.metadata, Bytes::new());
+//!
+//! // Sign the RAV
+//! let signed_rav = sign(&signer, &domain, rav).expect("failed to sign RAV");
+//! assert!(verify(&domain, &signed_rav, &signer.address()).is_ok());
+//! ```
+//!
+//! [EIP-712]: https://eips.ethereum.org/EIPS/eip-712 "EIP-712"
+//! [`signed_message`]: crate::signed_message
+
+use alloy::{
+    primitives::{Address, ChainId},
+    sol_types::{Eip712Domain, eip712_domain},
+};
+
+pub use self::{
+    aggregation::{AggregationError, aggregate_receipts},
+    rav::{ReceiptAggregateVoucher, SignedRav},
+    receipt::{Receipt, SignedReceipt},
+};
+
+mod aggregation;
+pub mod checks;
+mod rav;
+mod receipt;
+pub mod v1;
+
+/// The Graph Tally EIP-712 domain name
+const TALLY_EIP712_DOMAIN_NAME: &str = "GraphTallyCollector";
+
+/// The Graph Tally EIP-712 domain version
+const TALLY_EIP712_DOMAIN_VERSION: &str = "1";
+
+/// Solidity struct definitions of the Graph Tally EIP-712 messages.
+///
+/// The struct and field names are part of the EIP-712 type hash, so they must match the ones
+/// used by the `GraphTallyCollector` contract and the rest of the network's components.
+pub mod sol {
+    alloy::sol! {
+        /// EIP-712 receipt struct.
+        #[derive(Debug, PartialEq, Eq)]
+        struct Receipt {
+            bytes32 collection_id;
+            address payer;
+            address data_service;
+            address service_provider;
+            uint64 timestamp_ns;
+            uint64 nonce;
+            uint128 value;
+        }
+
+        /// EIP-712 Receipt Aggregate Voucher (RAV) struct.
+        #[derive(Debug, PartialEq, Eq)]
+        struct ReceiptAggregateVoucher {
+            bytes32 collectionId;
+            address payer;
+            address serviceProvider;
+            address dataService;
+            uint64 timestampNs;
+            uint128 valueAggregate;
+            bytes metadata;
+        }
+    }
+}
+
+/// Create the Graph Tally EIP-712 domain given a chain ID and the `GraphTallyCollector` contract
+/// address.
+pub fn eip712_domain(chain_id: ChainId, verifying_contract: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: TALLY_EIP712_DOMAIN_NAME,
+        version: TALLY_EIP712_DOMAIN_VERSION,
+        chain_id: chain_id,
+        verifying_contract: verifying_contract,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::sol_types::SolStruct;
+
+    use super::sol;
+
+    #[test]
+    fn receipt_eip712_type_matches_network_definition() {
+        //* When
+        let encoded_type = sol::Receipt::eip712_encode_type();
+
+        //* Then
+        assert_eq!(
+            encoded_type,
+            "Receipt(bytes32 collection_id,address payer,address data_service,\
+             address service_provider,uint64 timestamp_ns,uint64 nonce,uint128 value)"
+        );
+    }
+
+    #[test]
+    fn rav_eip712_type_matches_collector_contract_definition() {
+        //* When
+        let encoded_type = sol::ReceiptAggregateVoucher::eip712_encode_type();
+
+        //* Then
+        assert_eq!(
+            encoded_type,
+            "ReceiptAggregateVoucher(bytes32 collectionId,address payer,address serviceProvider,\
+             address dataService,uint64 timestampNs,uint128 valueAggregate,bytes metadata)"
+        );
+    }
+}
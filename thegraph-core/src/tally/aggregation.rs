@@ -0,0 +1,400 @@
+use std::collections::HashSet;
+
+use alloy::{
+    primitives::{Address, Bytes},
+    sol_types::Eip712Domain,
+};
+
+use super::{
+    rav::{ReceiptAggregateVoucher, SignedRav},
+    receipt::SignedReceipt,
+    sol,
+};
+use crate::{
+    collection_id::CollectionId,
+    signed_message::{AuthorizationError, SignerAuthorization, verify_authorized},
+};
+
+/// Errors that can occur when aggregating receipts into a RAV.
+#[derive(Debug, thiserror::Error)]
+pub enum AggregationError {
+    /// There are no receipts to aggregate
+    #[error("no receipts to aggregate")]
+    NoReceipts,
+
+    /// A receipt signature is invalid, or its signer is not authorized
+    #[error("invalid receipt signer")]
+    InvalidReceiptSigner(#[source] AuthorizationError),
+
+    /// The previous RAV signature is invalid, or its signer is not authorized
+    #[error("invalid previous RAV signer")]
+    InvalidRavSigner(#[source] AuthorizationError),
+
+    /// The same receipt, i.e., the same EIP-712 message, was found more than once
+    #[error("duplicate receipt")]
+    DuplicateReceipt,
+
+    /// The receipt collection ID does not match the expected collection ID
+    #[error("receipt collection ID `{received}` does not match the expected `{expected}`")]
+    CollectionIdMismatch {
+        /// The expected collection ID
+        expected: CollectionId,
+        /// The receipt collection ID
+        received: CollectionId,
+    },
+
+    /// The receipt payer does not match the expected payer
+    #[error("receipt payer `{received}` does not match the expected `{expected}`")]
+    PayerMismatch {
+        /// The expected payer address
+        expected: Address,
+        /// The receipt payer address
+        received: Address,
+    },
+
+    /// The receipt service provider does not match the expected service provider
+    #[error("receipt service provider `{received}` does not match the expected `{expected}`")]
+    ServiceProviderMismatch {
+        /// The expected service provider address
+        expected: Address,
+        /// The receipt service provider address
+        received: Address,
+    },
+
+    /// The receipt data service does not match the expected data service
+    #[error("receipt data service `{received}` does not match the expected `{expected}`")]
+    DataServiceMismatch {
+        /// The expected data service address
+        expected: Address,
+        /// The receipt data service address
+        received: Address,
+    },
+
+    /// The receipt timestamp is not newer than the previous RAV timestamp
+    #[error(
+        "receipt timestamp `{receipt_timestamp_ns}` is not after the previous RAV timestamp \
+         `{rav_timestamp_ns}`"
+    )]
+    ReceiptTimestampNotAfterRav {
+        /// The receipt timestamp, in nanoseconds
+        receipt_timestamp_ns: u64,
+        /// The previous RAV timestamp, in nanoseconds
+        rav_timestamp_ns: u64,
+    },
+
+    /// The aggregated value overflows
+    #[error("aggregated value overflow")]
+    ValueOverflow,
+}
+
+/// Aggregate a set of signed receipts, and the previous RAV, if any, into a new RAV.
+///
+/// This is a pure function, the returned RAV must be signed by the caller. The following checks
+/// are performed before aggregating the receipts:
+///
+/// - The receipts and the previous RAV are signed by an authorized signer.
+/// - There are no duplicated receipts, i.e., receipts with the same EIP-712 message hash. The
+///   signature is not used, as a receipt signature can be malleated without invalidating it.
+/// - All the receipts belong to the same collection, payer, service provider and data service as
+///   the previous RAV (or the first receipt, if there is no previous RAV).
+/// - All the receipts timestamps are newer than the previous RAV timestamp.
+///
+/// The new RAV timestamp is the newest receipt timestamp, and its value is the previous RAV value
+/// plus the value of all the receipts. The previous RAV metadata is carried over.
+pub fn aggregate_receipts<A>(
+    domain: &Eip712Domain,
+    receipts: &[SignedReceipt],
+    previous_rav: Option<&SignedRav>,
+    authorized_signers: &A,
+) -> Result<ReceiptAggregateVoucher, AggregationError>
+where
+    A: SignerAuthorization + ?Sized,
+{
+    let first_receipt = match receipts.first() {
+        Some(receipt) => &receipt.message,
+        None => return Err(AggregationError::NoReceipts),
+    };
+
+    // Use the previous RAV as the aggregation starting point, if any
+    let mut rav = match previous_rav {
+        Some(previous_rav) => {
+            verify_authorized(domain, previous_rav, authorized_signers)
+                .map_err(AggregationError::InvalidRavSigner)?;
+            previous_rav.message.clone()
+        }
+        None => ReceiptAggregateVoucher {
+            collection_id: first_receipt.collection_id,
+            payer: first_receipt.payer,
+            service_provider: first_receipt.service_provider,
+            data_service: first_receipt.data_service,
+            timestamp_ns: 0,
+            value_aggregate: 0,
+            metadata: Bytes::new(),
+        },
+    };
+    let previous_timestamp_ns = previous_rav.map(|rav| rav.message.timestamp_ns);
+
+    let mut message_hashes = HashSet::with_capacity(receipts.len());
+    for signed_receipt in receipts {
+        if !message_hashes.insert(signed_receipt.message_hash::<sol::Receipt>()) {
+            return Err(AggregationError::DuplicateReceipt);
+        }
+
+        verify_authorized(domain, signed_receipt, authorized_signers)
+            .map_err(AggregationError::InvalidReceiptSigner)?;
+
+        let receipt = &signed_receipt.message;
+        if receipt.collection_id != rav.collection_id {
+            return Err(AggregationError::CollectionIdMismatch {
+                expected: rav.collection_id,
+                received: receipt.collection_id,
+            });
+        }
+        if receipt.payer != rav.payer {
+            return Err(AggregationError::PayerMismatch {
+                expected: rav.payer,
+                received: receipt.payer,
+            });
+        }
+        if receipt.service_provider != rav.service_provider {
+            return Err(AggregationError::ServiceProviderMismatch {
+                expected: rav.service_provider,
+                received: receipt.service_provider,
+            });
+        }
+        if receipt.data_service != rav.data_service {
+            return Err(AggregationError::DataServiceMismatch {
+                expected: rav.data_service,
+                received: receipt.data_service,
+            });
+        }
+        if let Some(rav_timestamp_ns) = previous_timestamp_ns {
+            if receipt.timestamp_ns <= rav_timestamp_ns {
+                return Err(AggregationError::ReceiptTimestampNotAfterRav {
+                    receipt_timestamp_ns: receipt.timestamp_ns,
+                    rav_timestamp_ns,
+                });
+            }
+        }
+
+        rav.timestamp_ns = rav.timestamp_ns.max(receipt.timestamp_ns);
+        rav.value_aggregate = rav
+            .value_aggregate
+            .checked_add(receipt.value)
+            .ok_or(AggregationError::ValueOverflow)?;
+    }
+
+    Ok(rav)
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, Bytes, Signature, U256, address, uint},
+        signers::local::PrivateKeySigner,
+        sol_types::Eip712Domain,
+    };
+
+    use super::{AggregationError, aggregate_receipts};
+    use crate::{
+        CollectionId, collection_id,
+        signed_message::{AuthorizationError, sign},
+        tally::{Receipt, ReceiptAggregateVoucher, SignedReceipt, eip712_domain},
+    };
+
+    const COLLECTION_ID: CollectionId =
+        collection_id!("0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24");
+    const PAYER: Address = address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b");
+    const DATA_SERVICE: Address = address!("16def7e0108a5467a106dbd7537f8591f470342e");
+    const SERVICE_PROVIDER: Address = address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1");
+
+    /// Create a domain for testing
+    fn domain() -> Eip712Domain {
+        eip712_domain(1337, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"))
+    }
+
+    /// Create and sign a receipt for testing
+    fn receipt(signer: &PrivateKeySigner, timestamp_ns: u64, value: u128) -> SignedReceipt {
+        let receipt = Receipt {
+            collection_id: COLLECTION_ID,
+            payer: PAYER,
+            data_service: DATA_SERVICE,
+            service_provider: SERVICE_PROVIDER,
+            timestamp_ns,
+            nonce: timestamp_ns,
+            value,
+        };
+        sign(signer, &domain(), receipt).expect("failed to sign receipt")
+    }
+
+    #[test]
+    fn aggregate_receipts_into_a_new_rav() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let receipts = [
+            receipt(&signer, 20, 100),
+            receipt(&signer, 30, 200),
+            receipt(&signer, 10, 300),
+        ];
+
+        //* When
+        let result = aggregate_receipts(&domain(), &receipts, None, &[signer.address()][..]);
+
+        //* Then
+        assert_eq!(
+            result.expect("aggregation failed"),
+            ReceiptAggregateVoucher {
+                collection_id: COLLECTION_ID,
+                payer: PAYER,
+                service_provider: SERVICE_PROVIDER,
+                data_service: DATA_SERVICE,
+                timestamp_ns: 30,
+                value_aggregate: 600,
+                metadata: Bytes::new(),
+            }
+        );
+    }
+
+    #[test]
+    fn aggregate_receipts_on_top_of_the_previous_rav() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let signers = [signer.address()];
+
+        let previous_rav = aggregate_receipts(
+            &domain(),
+            &[receipt(&signer, 10, 100), receipt(&signer, 20, 200)],
+            None,
+            &signers[..],
+        )
+        .expect("aggregation failed");
+        let previous_rav = sign(&signer, &domain(), previous_rav).expect("failed to sign RAV");
+
+        let receipts = [receipt(&signer, 30, 300), receipt(&signer, 40, 400)];
+
+        //* When
+        let result = aggregate_receipts(&domain(), &receipts, Some(&previous_rav), &signers[..]);
+
+        //* Then
+        let rav = result.expect("aggregation failed");
+        assert_eq!(rav.timestamp_ns, 40);
+        assert_eq!(rav.value_aggregate, 1_000);
+    }
+
+    #[test]
+    fn aggregation_should_fail_with_receipts_older_than_the_previous_rav() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let signers = [signer.address()];
+
+        let previous_rav =
+            aggregate_receipts(&domain(), &[receipt(&signer, 20, 100)], None, &signers[..])
+                .expect("aggregation failed");
+        let previous_rav = sign(&signer, &domain(), previous_rav).expect("failed to sign RAV");
+
+        let receipts = [receipt(&signer, 30, 300), receipt(&signer, 20, 400)];
+
+        //* When
+        let result = aggregate_receipts(&domain(), &receipts, Some(&previous_rav), &signers[..]);
+
+        //* Then
+        assert!(matches!(
+            result,
+            Err(AggregationError::ReceiptTimestampNotAfterRav {
+                receipt_timestamp_ns: 20,
+                rav_timestamp_ns: 20,
+            })
+        ));
+    }
+
+    #[test]
+    fn aggregation_should_fail_with_receipts_from_different_collections() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+
+        let other_collection_id = CollectionId::from(PAYER);
+        let mut other_receipt = receipt(&signer, 30, 300).message;
+        other_receipt.collection_id = other_collection_id;
+        let other_receipt = sign(&signer, &domain(), other_receipt).expect("failed to sign");
+
+        let receipts = [receipt(&signer, 20, 100), other_receipt];
+
+        //* When
+        let result = aggregate_receipts(&domain(), &receipts, None, &[signer.address()][..]);
+
+        //* Then
+        assert!(matches!(
+            result,
+            Err(AggregationError::CollectionIdMismatch { expected, received })
+                if expected == COLLECTION_ID && received == other_collection_id
+        ));
+    }
+
+    #[test]
+    fn aggregation_should_fail_with_unauthorized_and_duplicate_receipts() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let unauthorized_signer = PrivateKeySigner::random();
+
+        let duplicate = receipt(&signer, 10, 100);
+
+        //* When
+        let unauthorized = aggregate_receipts(
+            &domain(),
+            &[receipt(&unauthorized_signer, 10, 100)],
+            None,
+            &[signer.address()][..],
+        );
+        let duplicated = aggregate_receipts(
+            &domain(),
+            &[duplicate.clone(), duplicate],
+            None,
+            &[signer.address()][..],
+        );
+
+        //* Then
+        assert!(matches!(
+            unauthorized,
+            Err(AggregationError::InvalidReceiptSigner(
+                AuthorizationError::UnauthorizedSigner(_)
+            ))
+        ));
+        assert!(matches!(
+            duplicated,
+            Err(AggregationError::DuplicateReceipt)
+        ));
+    }
+
+    #[test]
+    fn aggregation_should_fail_with_malleated_duplicate_receipts() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+
+        // The secp256k1 curve order
+        const SECP256K1N_ORDER: U256 =
+            uint!(0xFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFFEBAAEDCE6AF48A03BBFD25E8CD0364141_U256);
+
+        let original = receipt(&signer, 10, 100);
+
+        // A copy of the receipt with the flipped `s` value, i.e., a different but still valid
+        // signature
+        let mut malleated = original.clone();
+        malleated.signature = Signature::new(
+            original.signature.r(),
+            SECP256K1N_ORDER - original.signature.s(),
+            !original.signature.v(),
+        );
+        assert_ne!(malleated.signature_bytes(), original.signature_bytes());
+
+        //* When
+        let result = aggregate_receipts(
+            &domain(),
+            &[original, malleated],
+            None,
+            &[signer.address()][..],
+        );
+
+        //* Then
+        assert!(matches!(result, Err(AggregationError::DuplicateReceipt)));
+    }
+}
@@ -0,0 +1,661 @@
+//! Composable receipt validation checks.
+//!
+//! Validating a paid query receipt involves several checks: the signer is authorized, the
+//! collection is open, the timestamp is within an acceptable window, the value covers the agreed
+//! price, the receipt was not seen before, etc. This module provides a [`Check`] trait to
+//! implement such checks over [`SignedMessage`] receipts, and a [`CheckPipeline`] to run them in
+//! order.
+//!
+//! The following built-in checks are available:
+//!
+//! - [`SignatureCheck`]: The receipt is signed by an authorized signer.
+//! - [`TimestampWindowCheck`]: The receipt timestamp is within a window around the current time.
+//! - [`DeduplicationCheck`]: The receipt, identified by its [`MessageHash`], was not seen before.
+//!
+//! Custom checks can be implemented via the [`Check`] trait or created from a closure via
+//! [`check_fn`].
+//!
+//! Checks keeping track of the accepted messages, e.g., the [`DeduplicationCheck`], only record a
+//! message in the [`Check::commit`] step. The pipeline runs this step once all the checks passed,
+//! so a message rejected by any check is not recorded, and can be resubmitted.
+//!
+//! ## Example
+//! ```rust
+//! # use std::{collections::HashSet, time::Duration};
+//! # use thegraph_core::alloy::primitives::address;
+//! use thegraph_core::{
+//!     collection_id,
+//!     signed_message::sign,
+//!     tally::{
+//!         Receipt, SignedReceipt, eip712_domain,
+//!         checks::{
+//!             CheckFailureReason, CheckPipeline, DeduplicationCheck, SignatureCheck,
+//!             TimestampWindowCheck, check_fn,
+//!         },
+//!     },
+//! };
+//!
+//! let signer = thegraph_core::alloy::signers::local::PrivateKeySigner::random();
+//! let domain = eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+//!
+//! // The checks are run in the order they are added to the pipeline
+//! let pipeline = CheckPipeline::new()
+//!     .with_check(SignatureCheck::new(domain.clone(), HashSet::from([signer.address()])))
+//!     .with_check(TimestampWindowCheck::new(Duration::from_secs(30), Duration::from_secs(5)))
+//!     .with_check(check_fn("min_value", |receipt: &SignedReceipt| {
+//!         if receipt.message.value < 100 {
+//!             return Err(CheckFailureReason::other("value below the agreed price"));
+//!         }
+//!         Ok(())
+//!     }))
+//!     .with_check(DeduplicationCheck::new());
+//!
+//! let receipt = Receipt {
+//!     collection_id: collection_id!(
+//!         "0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"
+//!     ),
+//!     payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+//!     data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+//!     service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+//!     timestamp_ns: std::time::SystemTime::now()
+//!         .duration_since(std::time::UNIX_EPOCH)
+//!         .unwrap()
+//!         .as_nanos() as u64,
+//!     nonce: 42,
+//!     value: 1_000,
+//! };
+//! let signed_receipt = sign(&signer, &domain, receipt).expect("failed to sign receipt");
+//!
+//! // The first time the receipt is accepted, the second time it is a duplicate
+//! assert!(pipeline.check(&signed_receipt).is_ok());
+//! assert!(pipeline.check(&signed_receipt).is_err());
+//! ```
+
+use std::{
+    collections::HashSet,
+    marker::PhantomData,
+    sync::{Mutex, PoisonError},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use alloy::sol_types::{Eip712Domain, SolStruct};
+
+use crate::signed_message::{
+    AuthorizationError, MessageHash, SignedMessage, SignerAuthorization, ToSolStruct,
+    verify_authorized,
+};
+
+/// The reason a check failed.
+#[derive(Debug, thiserror::Error)]
+pub enum CheckFailureReason {
+    /// The signature is invalid, or the signer is not authorized
+    #[error(transparent)]
+    Signature(#[from] AuthorizationError),
+
+    /// The timestamp is older than the window's lower bound
+    #[error("timestamp `{timestamp_ns}` is older than `{min_timestamp_ns}`")]
+    TimestampTooOld {
+        /// The message timestamp, in nanoseconds
+        timestamp_ns: u64,
+        /// The oldest accepted timestamp, in nanoseconds
+        min_timestamp_ns: u64,
+    },
+
+    /// The timestamp is newer than the window's upper bound
+    #[error("timestamp `{timestamp_ns}` is newer than `{max_timestamp_ns}`")]
+    TimestampTooNew {
+        /// The message timestamp, in nanoseconds
+        timestamp_ns: u64,
+        /// The newest accepted timestamp, in nanoseconds
+        max_timestamp_ns: u64,
+    },
+
+    /// The message was already seen
+    #[error("duplicate message `{}`", alloy::primitives::hex::encode_prefixed(.0.as_bytes()))]
+    DuplicateMessage(MessageHash),
+
+    /// Custom check failure
+    #[error(transparent)]
+    Other(Box<dyn std::error::Error + Send + Sync + 'static>),
+}
+
+impl CheckFailureReason {
+    /// Create a custom check failure reason.
+    pub fn other(err: impl Into<Box<dyn std::error::Error + Send + Sync + 'static>>) -> Self {
+        Self::Other(err.into())
+    }
+}
+
+/// A failed check, and the reason it failed.
+#[derive(Debug, thiserror::Error)]
+#[error("check `{check}` failed: {reason}")]
+pub struct CheckError {
+    /// The name of the failed check
+    pub check: &'static str,
+    /// The reason the check failed
+    pub reason: CheckFailureReason,
+}
+
+/// A check over a signed message.
+pub trait Check<M> {
+    /// The check name, used to report failures.
+    fn name(&self) -> &'static str;
+
+    /// Check the signed message.
+    ///
+    /// Returns `Ok(())` if the check passes. Otherwise, returns the failure reason.
+    fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason>;
+
+    /// Record the signed message as accepted.
+    ///
+    /// Called by the [`CheckPipeline`] once the message passed all the checks. Checks keeping
+    /// track of the accepted messages must record them here, and not in [`Check::check`].
+    ///
+    /// Returns the failure reason if the message can no longer be accepted, e.g., it was accepted
+    /// concurrently. Does nothing by default.
+    fn commit(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        let _ = message;
+        Ok(())
+    }
+}
+
+/// A message carrying a Unix timestamp, in nanoseconds.
+pub trait Timestamped {
+    /// Get the message timestamp, in nanoseconds.
+    fn timestamp_ns(&self) -> u64;
+}
+
+impl Timestamped for super::Receipt {
+    fn timestamp_ns(&self) -> u64 {
+        self.timestamp_ns
+    }
+}
+
+impl Timestamped for super::v1::Receipt {
+    fn timestamp_ns(&self) -> u64 {
+        self.timestamp_ns
+    }
+}
+
+/// Create a custom check from a closure.
+pub fn check_fn<M, F>(name: &'static str, f: F) -> CheckFn<F>
+where
+    F: Fn(&SignedMessage<M>) -> Result<(), CheckFailureReason>,
+{
+    CheckFn { name, f }
+}
+
+/// A custom check created from a closure.
+///
+/// See [`check_fn`].
+#[derive(Clone)]
+pub struct CheckFn<F> {
+    name: &'static str,
+    f: F,
+}
+
+impl<M, F> Check<M> for CheckFn<F>
+where
+    F: Fn(&SignedMessage<M>) -> Result<(), CheckFailureReason>,
+{
+    fn name(&self) -> &'static str {
+        self.name
+    }
+
+    fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        (self.f)(message)
+    }
+}
+
+/// Check the message is signed by an authorized signer.
+///
+/// See [`verify_authorized`].
+pub struct SignatureCheck<A, MSol> {
+    domain: Eip712Domain,
+    authorized_signers: A,
+    _sol: PhantomData<fn() -> MSol>,
+}
+
+impl<A, MSol> SignatureCheck<A, MSol> {
+    /// Create a new signature check given the EIP-712 domain and the authorized signers.
+    pub fn new(domain: Eip712Domain, authorized_signers: A) -> Self {
+        Self {
+            domain,
+            authorized_signers,
+            _sol: PhantomData,
+        }
+    }
+}
+
+impl<M, MSol, A> Check<M> for SignatureCheck<A, MSol>
+where
+    M: ToSolStruct<MSol>,
+    MSol: SolStruct,
+    A: SignerAuthorization,
+{
+    fn name(&self) -> &'static str {
+        "signature"
+    }
+
+    fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        verify_authorized(&self.domain, message, &self.authorized_signers)?;
+        Ok(())
+    }
+}
+
+/// Check the message timestamp is within a window around the current time.
+///
+/// The window spans from `now - max_age` to `now + max_clock_skew`.
+pub struct TimestampWindowCheck {
+    max_age: Duration,
+    max_clock_skew: Duration,
+    clock: fn() -> SystemTime,
+}
+
+impl TimestampWindowCheck {
+    /// Create a new timestamp window check.
+    pub fn new(max_age: Duration, max_clock_skew: Duration) -> Self {
+        Self {
+            max_age,
+            max_clock_skew,
+            clock: SystemTime::now,
+        }
+    }
+
+    /// Set the clock used to get the current time.
+    ///
+    /// Defaults to [`SystemTime::now`].
+    pub fn with_clock(mut self, clock: fn() -> SystemTime) -> Self {
+        self.clock = clock;
+        self
+    }
+}
+
+impl<M: Timestamped> Check<M> for TimestampWindowCheck {
+    fn name(&self) -> &'static str {
+        "timestamp_window"
+    }
+
+    fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        let now = (self.clock)()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default();
+        let min_timestamp_ns = now.saturating_sub(self.max_age).as_nanos() as u64;
+        let max_timestamp_ns = now.saturating_add(self.max_clock_skew).as_nanos() as u64;
+
+        let timestamp_ns = message.message.timestamp_ns();
+        if timestamp_ns < min_timestamp_ns {
+            return Err(CheckFailureReason::TimestampTooOld {
+                timestamp_ns,
+                min_timestamp_ns,
+            });
+        }
+        if timestamp_ns > max_timestamp_ns {
+            return Err(CheckFailureReason::TimestampTooNew {
+                timestamp_ns,
+                max_timestamp_ns,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Check the message was not seen before.
+///
+/// Messages are identified by their [`MessageHash`], i.e., the same message signed by different
+/// signers is considered a duplicate. Messages are only recorded as seen in the [`Check::commit`]
+/// step, i.e., once they passed all the pipeline checks.
+pub struct DeduplicationCheck<MSol> {
+    seen: Mutex<HashSet<MessageHash>>,
+    _sol: PhantomData<fn() -> MSol>,
+}
+
+impl<MSol> DeduplicationCheck<MSol> {
+    /// Create a new deduplication check.
+    pub fn new() -> Self {
+        Self {
+            seen: Mutex::new(HashSet::new()),
+            _sol: PhantomData,
+        }
+    }
+
+    /// Forget all the seen messages, e.g., once they are aggregated into a RAV.
+    pub fn clear(&self) {
+        self.seen
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .clear();
+    }
+}
+
+impl<MSol> Default for DeduplicationCheck<MSol> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<M, MSol> Check<M> for DeduplicationCheck<MSol>
+where
+    M: ToSolStruct<MSol>,
+    MSol: SolStruct,
+{
+    fn name(&self) -> &'static str {
+        "deduplication"
+    }
+
+    fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        let hash = message.message_hash::<MSol>();
+        let seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        if seen.contains(&hash) {
+            return Err(CheckFailureReason::DuplicateMessage(hash));
+        }
+        Ok(())
+    }
+
+    fn commit(&self, message: &SignedMessage<M>) -> Result<(), CheckFailureReason> {
+        let hash = message.message_hash::<MSol>();
+        let mut seen = self.seen.lock().unwrap_or_else(PoisonError::into_inner);
+        if !seen.insert(hash.clone()) {
+            return Err(CheckFailureReason::DuplicateMessage(hash));
+        }
+        Ok(())
+    }
+}
+
+/// The outcome of checking a batch of messages.
+///
+/// See [`CheckPipeline::check_batch`].
+#[derive(Debug)]
+pub struct BatchOutcome<'a, M> {
+    /// The messages that passed all the checks
+    pub accepted: Vec<&'a SignedMessage<M>>,
+    /// The messages that failed a check, and the failed check
+    pub rejected: Vec<(&'a SignedMessage<M>, CheckError)>,
+}
+
+/// An ordered set of checks.
+///
+/// Checks are run in the order they were added to the pipeline. Once a message passed all the
+/// checks, it is committed, see [`Check::commit`].
+pub struct CheckPipeline<M> {
+    checks: Vec<Box<dyn Check<M> + Send + Sync>>,
+}
+
+impl<M> CheckPipeline<M> {
+    /// Create a new empty pipeline.
+    pub fn new() -> Self {
+        Self { checks: Vec::new() }
+    }
+
+    /// Append a check to the pipeline.
+    pub fn with_check(mut self, check: impl Check<M> + Send + Sync + 'static) -> Self {
+        self.checks.push(Box::new(check));
+        self
+    }
+
+    /// Run the checks in order, stopping at the first failure.
+    ///
+    /// If all the checks pass, the message is committed.
+    pub fn check(&self, message: &SignedMessage<M>) -> Result<(), CheckError> {
+        for check in &self.checks {
+            check.check(message).map_err(|reason| CheckError {
+                check: check.name(),
+                reason,
+            })?;
+        }
+        self.commit(message)
+    }
+
+    /// Run all the checks in order, collecting all the failures.
+    ///
+    /// If all the checks pass, the message is committed.
+    pub fn check_all(&self, message: &SignedMessage<M>) -> Result<(), Vec<CheckError>> {
+        let errors = self
+            .checks
+            .iter()
+            .filter_map(|check| {
+                check.check(message).err().map(|reason| CheckError {
+                    check: check.name(),
+                    reason,
+                })
+            })
+            .collect::<Vec<_>>();
+        if !errors.is_empty() {
+            return Err(errors);
+        }
+        self.commit(message).map_err(|err| vec![err])
+    }
+
+    /// Commit the message on all the checks, in order, stopping at the first failure.
+    fn commit(&self, message: &SignedMessage<M>) -> Result<(), CheckError> {
+        for check in &self.checks {
+            check.commit(message).map_err(|reason| CheckError {
+                check: check.name(),
+                reason,
+            })?;
+        }
+        Ok(())
+    }
+
+    /// Check a batch of messages, partitioning them into accepted and rejected messages.
+    ///
+    /// Each message is checked with [`CheckPipeline::check`], in order.
+    pub fn check_batch<'a>(
+        &self,
+        messages: impl IntoIterator<Item = &'a SignedMessage<M>>,
+    ) -> BatchOutcome<'a, M> {
+        let mut outcome = BatchOutcome {
+            accepted: Vec::new(),
+            rejected: Vec::new(),
+        };
+        for message in messages {
+            match self.check(message) {
+                Ok(()) => outcome.accepted.push(message),
+                Err(err) => outcome.rejected.push((message, err)),
+            }
+        }
+        outcome
+    }
+}
+
+impl<M> Default for CheckPipeline<M> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{
+        collections::HashSet,
+        sync::atomic::{AtomicBool, Ordering},
+        time::{Duration, SystemTime, UNIX_EPOCH},
+    };
+
+    use alloy::{
+        primitives::{Address, address},
+        signers::local::PrivateKeySigner,
+        sol_types::Eip712Domain,
+    };
+
+    use super::{
+        CheckFailureReason, CheckPipeline, DeduplicationCheck, SignatureCheck,
+        TimestampWindowCheck, check_fn,
+    };
+    use crate::{
+        collection_id,
+        signed_message::{AuthorizationError, sign},
+        tally::{Receipt, SignedReceipt, eip712_domain},
+    };
+
+    /// The fixed current time used by the tests, in seconds since the Unix epoch
+    const NOW_SECS: u64 = 1_700_000_000;
+
+    /// Test clock returning a fixed time
+    fn clock() -> SystemTime {
+        UNIX_EPOCH + Duration::from_secs(NOW_SECS)
+    }
+
+    /// Create a domain for testing
+    fn domain() -> Eip712Domain {
+        eip712_domain(1337, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"))
+    }
+
+    /// Create and sign a receipt for testing
+    fn receipt(signer: &PrivateKeySigner, timestamp_secs: u64, value: u128) -> SignedReceipt {
+        let receipt = Receipt {
+            collection_id: collection_id!(
+                "0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"
+            ),
+            payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+            data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+            service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+            timestamp_ns: Duration::from_secs(timestamp_secs).as_nanos() as u64,
+            nonce: 1,
+            value,
+        };
+        sign(signer, &domain(), receipt).expect("failed to sign receipt")
+    }
+
+    /// Create a pipeline with all the built-in checks for testing
+    fn pipeline(authorized_signers: HashSet<Address>) -> CheckPipeline<Receipt> {
+        CheckPipeline::new()
+            .with_check(SignatureCheck::new(domain(), authorized_signers))
+            .with_check(
+                TimestampWindowCheck::new(Duration::from_secs(30), Duration::from_secs(5))
+                    .with_clock(clock),
+            )
+            .with_check(check_fn("min_value", |receipt: &SignedReceipt| {
+                if receipt.message.value < 100 {
+                    return Err(CheckFailureReason::other("value below the agreed price"));
+                }
+                Ok(())
+            }))
+            .with_check(DeduplicationCheck::new())
+    }
+
+    #[test]
+    fn valid_receipt_passes_all_checks_once() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let pipeline = pipeline(HashSet::from([signer.address()]));
+
+        let receipt = receipt(&signer, NOW_SECS, 100);
+
+        //* When
+        let first = pipeline.check(&receipt);
+        let second = pipeline.check(&receipt);
+
+        //* Then
+        assert!(first.is_ok());
+        let error = second.expect_err("duplicate receipt should fail");
+        assert_eq!(error.check, "deduplication");
+        assert!(matches!(
+            error.reason,
+            CheckFailureReason::DuplicateMessage(hash) if hash == receipt.message_hash::<crate::tally::sol::Receipt>()
+        ));
+    }
+
+    #[test]
+    fn check_short_circuits_at_the_first_failure() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let pipeline = pipeline(HashSet::from([PrivateKeySigner::random().address()]));
+
+        // Unauthorized signer, too old and below the agreed price
+        let receipt = receipt(&signer, NOW_SECS - 60, 10);
+
+        //* When
+        let result = pipeline.check(&receipt);
+
+        //* Then
+        let error = result.expect_err("check should fail");
+        assert_eq!(error.check, "signature");
+        assert!(matches!(
+            error.reason,
+            CheckFailureReason::Signature(AuthorizationError::UnauthorizedSigner(_))
+        ));
+    }
+
+    #[test]
+    fn check_all_collects_all_failures_in_order() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let pipeline = pipeline(HashSet::from([PrivateKeySigner::random().address()]));
+
+        // Unauthorized signer, too new and below the agreed price
+        let receipt = receipt(&signer, NOW_SECS + 60, 10);
+
+        //* When
+        let result = pipeline.check_all(&receipt);
+
+        //* Then
+        let errors = result.expect_err("check should fail");
+        let failed_checks = errors.iter().map(|err| err.check).collect::<Vec<_>>();
+        assert_eq!(
+            failed_checks,
+            ["signature", "timestamp_window", "min_value"]
+        );
+        assert!(matches!(
+            errors[1].reason,
+            CheckFailureReason::TimestampTooNew { .. }
+        ));
+    }
+
+    #[test]
+    fn check_batch_partitions_accepted_and_rejected_receipts() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let pipeline = pipeline(HashSet::from([signer.address()]));
+
+        let valid = receipt(&signer, NOW_SECS - 1, 100);
+        let too_old = receipt(&signer, NOW_SECS - 60, 100);
+        let receipts = [valid.clone(), too_old, valid];
+
+        //* When
+        let outcome = pipeline.check_batch(&receipts);
+
+        //* Then
+        assert_eq!(outcome.accepted, [&receipts[0]]);
+        let rejected = outcome
+            .rejected
+            .iter()
+            .map(|(_, err)| err.check)
+            .collect::<Vec<_>>();
+        assert_eq!(rejected, ["timestamp_window", "deduplication"]);
+    }
+
+    #[test]
+    fn rejected_receipt_is_accepted_on_retry() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+
+        // A check failing only the first time, e.g., the collection was not open yet
+        let collection_open = AtomicBool::new(false);
+        let pipeline = CheckPipeline::new()
+            .with_check(DeduplicationCheck::new())
+            .with_check(check_fn("collection_open", move |_: &SignedReceipt| {
+                if !collection_open.swap(true, Ordering::Relaxed) {
+                    return Err(CheckFailureReason::other("collection not open"));
+                }
+                Ok(())
+            }));
+
+        let receipt = receipt(&signer, NOW_SECS, 100);
+
+        //* When
+        let rejected = pipeline.check_all(&receipt);
+        let retried = pipeline.check(&receipt);
+        let duplicate = pipeline.check(&receipt);
+
+        //* Then
+        let errors = rejected.expect_err("first check should fail");
+        let failed_checks = errors.iter().map(|err| err.check).collect::<Vec<_>>();
+        assert_eq!(failed_checks, ["collection_open"]);
+        assert!(retried.is_ok());
+        assert_eq!(
+            duplicate.expect_err("duplicate receipt should fail").check,
+            "deduplication"
+        );
+    }
+}
@@ -0,0 +1,53 @@
+use alloy::primitives::{Address, Bytes};
+
+use super::sol;
+use crate::{
+    collection_id::CollectionId,
+    signed_message::{SignedMessage, ToSolStruct},
+};
+
+/// An EIP-712 signed [`ReceiptAggregateVoucher`].
+pub type SignedRav = SignedMessage<ReceiptAggregateVoucher>;
+
+/// A Graph Tally Receipt Aggregate Voucher (RAV).
+///
+/// A RAV aggregates the value of a collection's receipts. Each RAV supersedes the previous one,
+/// its value being the cumulative value of all the receipts aggregated so far. The signed RAV can
+/// be redeemed on-chain via the `GraphTallyCollector` contract.
+///
+/// See [`aggregate_receipts`] for more details.
+///
+/// [`aggregate_receipts`]: super::aggregate_receipts
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ReceiptAggregateVoucher {
+    /// The collection ID the aggregated receipts belong to.
+    pub collection_id: CollectionId,
+    /// The address of the payer the aggregated receipts were issued by.
+    pub payer: Address,
+    /// The address of the service provider the aggregated receipts were issued to.
+    pub service_provider: Address,
+    /// The address of the data service the aggregated receipts were issued for.
+    pub data_service: Address,
+    /// The Unix timestamp, in nanoseconds, of the newest aggregated receipt.
+    pub timestamp_ns: u64,
+    /// The cumulative value of the aggregated receipts, in GRT wei.
+    pub value_aggregate: u128,
+    /// Arbitrary metadata attached to the RAV.
+    pub metadata: Bytes,
+}
+
+impl ToSolStruct<sol::ReceiptAggregateVoucher> for ReceiptAggregateVoucher {
+    fn to_sol_struct(&self) -> sol::ReceiptAggregateVoucher {
+        sol::ReceiptAggregateVoucher {
+            collectionId: self.collection_id.into_inner(),
+            payer: self.payer,
+            serviceProvider: self.service_provider,
+            dataService: self.data_service,
+            timestampNs: self.timestamp_ns,
+            valueAggregate: self.value_aggregate,
+            metadata: self.metadata.clone(),
+        }
+    }
+}
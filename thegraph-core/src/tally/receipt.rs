@@ -0,0 +1,50 @@
+use alloy::primitives::Address;
+
+use super::sol;
+use crate::{
+    collection_id::CollectionId,
+    signed_message::{SignedMessage, ToSolStruct},
+};
+
+/// An EIP-712 signed [`Receipt`].
+pub type SignedReceipt = SignedMessage<Receipt>;
+
+/// A Graph Tally receipt.
+///
+/// A receipt is issued by the payer for every paid request sent to a data service's service
+/// provider. It is identified by its collection ID, and it is aggregated, together with the
+/// rest of the collection's receipts, into a [`ReceiptAggregateVoucher`].
+///
+/// [`ReceiptAggregateVoucher`]: super::ReceiptAggregateVoucher
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// The collection ID the receipt belongs to.
+    pub collection_id: CollectionId,
+    /// The address of the payer the receipt was issued by.
+    pub payer: Address,
+    /// The address of the data service the receipt was issued for.
+    pub data_service: Address,
+    /// The address of the service provider the receipt was issued to.
+    pub service_provider: Address,
+    /// The Unix timestamp, in nanoseconds, when the receipt was issued.
+    pub timestamp_ns: u64,
+    /// A random nonce used to distinguish receipts with the same timestamp.
+    pub nonce: u64,
+    /// The value of the receipt, in GRT wei.
+    pub value: u128,
+}
+
+impl ToSolStruct<sol::Receipt> for Receipt {
+    fn to_sol_struct(&self) -> sol::Receipt {
+        sol::Receipt {
+            collection_id: self.collection_id.into_inner(),
+            payer: self.payer,
+            data_service: self.data_service,
+            service_provider: self.service_provider,
+            timestamp_ns: self.timestamp_ns,
+            nonce: self.nonce,
+            value: self.value,
+        }
+    }
+}
@@ -0,0 +1,306 @@
+//! Legacy Graph Tally (TAP v1) receipts and Receipt Aggregate Vouchers (RAVs).
+//!
+//! Before _Horizon_, receipts and RAVs were keyed by the [`AllocationId`] of the indexer's
+//! allocation instead of a [`CollectionId`], and did not include the payer, service provider and
+//! data service addresses. Payments for older allocations still use these messages.
+//!
+//! This module provides the legacy message types, their EIP-712 domain, and migration helpers
+//! that map allocation-keyed state onto collection-keyed state, i.e., a collection ID is the
+//! allocation ID address left-padded to 32 bytes:
+//!
+//! - [`Receipt::into_v2`]: Converts a legacy receipt into a [`Receipt`](super::Receipt).
+//! - [`ReceiptAggregateVoucher::into_v2`]: Converts a legacy RAV into a
+//!   [`ReceiptAggregateVoucher`](super::ReceiptAggregateVoucher).
+//! - [`migrate_allocation_keyed`]: Re-keys any allocation-keyed state by collection ID.
+//!
+//! Note that signatures are not migrated, as the EIP-712 signing hashes of the legacy and the
+//! Horizon messages are different.
+
+use alloy::{
+    primitives::{Address, Bytes, ChainId},
+    sol_types::{Eip712Domain, eip712_domain},
+};
+
+use crate::{
+    allocation_id::AllocationId,
+    collection_id::CollectionId,
+    signed_message::{SignedMessage, ToSolStruct},
+};
+
+/// The legacy Graph Tally EIP-712 domain name
+const TAP_V1_EIP712_DOMAIN_NAME: &str = "TAP";
+
+/// The legacy Graph Tally EIP-712 domain version
+const TAP_V1_EIP712_DOMAIN_VERSION: &str = "1";
+
+/// Solidity struct definitions of the legacy Graph Tally EIP-712 messages.
+///
+/// The struct and field names are part of the EIP-712 type hash, so they must match the ones
+/// used by the `TAPVerifier` contract and the rest of the network's components.
+pub mod sol {
+    alloy::sol! {
+        /// EIP-712 legacy receipt struct.
+        #[derive(Debug, PartialEq, Eq)]
+        struct Receipt {
+            address allocation_id;
+            uint64 timestamp_ns;
+            uint64 nonce;
+            uint128 value;
+        }
+
+        /// EIP-712 legacy Receipt Aggregate Voucher (RAV) struct.
+        #[derive(Debug, PartialEq, Eq)]
+        struct ReceiptAggregateVoucher {
+            address allocationId;
+            uint64 timestampNs;
+            uint128 valueAggregate;
+        }
+    }
+}
+
+/// Create the legacy Graph Tally EIP-712 domain given a chain ID and the `TAPVerifier` contract
+/// address.
+pub fn eip712_domain(chain_id: ChainId, verifying_contract: Address) -> Eip712Domain {
+    eip712_domain! {
+        name: TAP_V1_EIP712_DOMAIN_NAME,
+        version: TAP_V1_EIP712_DOMAIN_VERSION,
+        chain_id: chain_id,
+        verifying_contract: verifying_contract,
+    }
+}
+
+/// The addresses of the parties involved in a collection's payments.
+///
+/// Legacy messages do not include them, so they must be provided when migrating them.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub struct CollectionParties {
+    /// The address of the payer
+    pub payer: Address,
+    /// The address of the data service
+    pub data_service: Address,
+    /// The address of the service provider
+    pub service_provider: Address,
+}
+
+/// An EIP-712 signed legacy [`Receipt`].
+pub type SignedReceipt = SignedMessage<Receipt>;
+
+/// A legacy Graph Tally receipt.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Receipt {
+    /// The allocation ID the receipt belongs to.
+    pub allocation_id: AllocationId,
+    /// The Unix timestamp, in nanoseconds, when the receipt was issued.
+    pub timestamp_ns: u64,
+    /// A random nonce used to distinguish receipts with the same timestamp.
+    pub nonce: u64,
+    /// The value of the receipt, in GRT wei.
+    pub value: u128,
+}
+
+impl Receipt {
+    /// Get the collection ID corresponding to the receipt's allocation ID.
+    pub fn collection_id(&self) -> CollectionId {
+        self.allocation_id.into()
+    }
+
+    /// Convert the legacy receipt into a [`Receipt`](super::Receipt) of the collection
+    /// corresponding to the receipt's allocation ID.
+    pub fn into_v2(self, parties: &CollectionParties) -> super::Receipt {
+        super::Receipt {
+            collection_id: self.collection_id(),
+            payer: parties.payer,
+            data_service: parties.data_service,
+            service_provider: parties.service_provider,
+            timestamp_ns: self.timestamp_ns,
+            nonce: self.nonce,
+            value: self.value,
+        }
+    }
+}
+
+impl ToSolStruct<sol::Receipt> for Receipt {
+    fn to_sol_struct(&self) -> sol::Receipt {
+        sol::Receipt {
+            allocation_id: self.allocation_id.into_inner(),
+            timestamp_ns: self.timestamp_ns,
+            nonce: self.nonce,
+            value: self.value,
+        }
+    }
+}
+
+/// An EIP-712 signed legacy [`ReceiptAggregateVoucher`].
+pub type SignedRav = SignedMessage<ReceiptAggregateVoucher>;
+
+/// A legacy Graph Tally Receipt Aggregate Voucher (RAV).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct ReceiptAggregateVoucher {
+    /// The allocation ID the aggregated receipts belong to.
+    pub allocation_id: AllocationId,
+    /// The Unix timestamp, in nanoseconds, of the newest aggregated receipt.
+    pub timestamp_ns: u64,
+    /// The cumulative value of the aggregated receipts, in GRT wei.
+    pub value_aggregate: u128,
+}
+
+impl ReceiptAggregateVoucher {
+    /// Get the collection ID corresponding to the RAV's allocation ID.
+    pub fn collection_id(&self) -> CollectionId {
+        self.allocation_id.into()
+    }
+
+    /// Convert the legacy RAV into a [`ReceiptAggregateVoucher`](super::ReceiptAggregateVoucher)
+    /// of the collection corresponding to the RAV's allocation ID.
+    ///
+    /// The resulting RAV has no metadata.
+    pub fn into_v2(self, parties: &CollectionParties) -> super::ReceiptAggregateVoucher {
+        super::ReceiptAggregateVoucher {
+            collection_id: self.collection_id(),
+            payer: parties.payer,
+            service_provider: parties.service_provider,
+            data_service: parties.data_service,
+            timestamp_ns: self.timestamp_ns,
+            value_aggregate: self.value_aggregate,
+            metadata: Bytes::new(),
+        }
+    }
+}
+
+impl ToSolStruct<sol::ReceiptAggregateVoucher> for ReceiptAggregateVoucher {
+    fn to_sol_struct(&self) -> sol::ReceiptAggregateVoucher {
+        sol::ReceiptAggregateVoucher {
+            allocationId: self.allocation_id.into_inner(),
+            timestampNs: self.timestamp_ns,
+            valueAggregate: self.value_aggregate,
+        }
+    }
+}
+
+/// Re-key allocation-keyed state by collection ID.
+///
+/// Each allocation ID is converted into its corresponding collection ID, i.e., the allocation ID
+/// address left-padded to 32 bytes. The output collection can be any collection of
+/// `(CollectionId, V)` pairs, e.g., a [`HashMap`](std::collections::HashMap).
+///
+/// ```rust
+/// use std::collections::BTreeMap;
+///
+/// use thegraph_core::{CollectionId, allocation_id, collection_id, tally::v1::migrate_allocation_keyed};
+///
+/// let fees = BTreeMap::from([(allocation_id!("3e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"), 1_000u128)]);
+///
+/// let fees: BTreeMap<CollectionId, u128> = migrate_allocation_keyed(fees);
+/// assert_eq!(
+///     fees.get(&collection_id!("0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24")),
+///     Some(&1_000)
+/// );
+/// ```
+pub fn migrate_allocation_keyed<V, C>(state: impl IntoIterator<Item = (AllocationId, V)>) -> C
+where
+    C: FromIterator<(CollectionId, V)>,
+{
+    state
+        .into_iter()
+        .map(|(allocation_id, value)| (allocation_id.into(), value))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use alloy::{
+        primitives::{Address, address},
+        signers::local::PrivateKeySigner,
+        sol_types::SolStruct,
+    };
+
+    use super::{CollectionParties, Receipt, ReceiptAggregateVoucher, eip712_domain, sol};
+    use crate::{
+        AllocationId, allocation_id, collection_id,
+        signed_message::{sign, verify},
+        tally,
+    };
+
+    const ALLOCATION_ID: AllocationId = allocation_id!("3e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24");
+    const VERIFIER: Address = address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421");
+
+    /// Create the parties for testing
+    fn parties() -> CollectionParties {
+        CollectionParties {
+            payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+            data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+            service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+        }
+    }
+
+    #[test]
+    fn legacy_eip712_types_match_verifier_contract_definition() {
+        //* Then
+        assert_eq!(
+            sol::Receipt::eip712_encode_type(),
+            "Receipt(address allocation_id,uint64 timestamp_ns,uint64 nonce,uint128 value)"
+        );
+        assert_eq!(
+            sol::ReceiptAggregateVoucher::eip712_encode_type(),
+            "ReceiptAggregateVoucher(address allocationId,uint64 timestampNs,uint128 valueAggregate)"
+        );
+    }
+
+    #[test]
+    fn sign_and_verify_legacy_receipt() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let domain = eip712_domain(1337, VERIFIER);
+
+        let receipt = Receipt {
+            allocation_id: ALLOCATION_ID,
+            timestamp_ns: 10,
+            nonce: 1,
+            value: 100,
+        };
+
+        //* When
+        let signed_receipt = sign(&signer, &domain, receipt).expect("failed to sign receipt");
+
+        //* Then
+        assert!(verify(&domain, &signed_receipt, &signer.address()).is_ok());
+
+        // The legacy and the Horizon domains are not interchangeable
+        let horizon_domain = tally::eip712_domain(1337, VERIFIER);
+        assert!(verify(&horizon_domain, &signed_receipt, &signer.address()).is_err());
+    }
+
+    #[test]
+    fn migrate_legacy_receipt_and_rav_to_collection_keyed() {
+        //* Given
+        let receipt = Receipt {
+            allocation_id: ALLOCATION_ID,
+            timestamp_ns: 10,
+            nonce: 1,
+            value: 100,
+        };
+        let rav = ReceiptAggregateVoucher {
+            allocation_id: ALLOCATION_ID,
+            timestamp_ns: 10,
+            value_aggregate: 100,
+        };
+
+        //* When
+        let receipt = receipt.into_v2(&parties());
+        let rav = rav.into_v2(&parties());
+
+        //* Then
+        let expected_collection_id =
+            collection_id!("0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24");
+        assert_eq!(receipt.collection_id, expected_collection_id);
+        assert_eq!(receipt.payer, parties().payer);
+        assert_eq!(rav.collection_id, expected_collection_id);
+        assert_eq!(rav.value_aggregate, 100);
+
+        // The collection ID maps back to the original allocation ID
+        assert_eq!(AllocationId::from(rav.collection_id), ALLOCATION_ID);
+    }
+}
@@ -16,6 +16,43 @@ pub mod compat_graphql_parser {
             Document::new(self.to_string())
         }
     }
+
+    /// Get the kind of the operation to execute, if it is not a query operation.
+    ///
+    /// If the GraphQL document cannot be parsed, or the operation cannot be determined, it is
+    /// considered a query operation, and left to the server to reject.
+    #[cfg(any(feature = "reqwest", feature = "server", feature = "tower"))]
+    pub(crate) fn non_query_operation(
+        req: &crate::http::request::RequestParameters,
+    ) -> Option<&'static str> {
+        use graphql_parser::query::{Definition, OperationDefinition};
+
+        let document = graphql_parser::parse_query::<&str>(req.query.as_str()).ok()?;
+        document
+            .definitions
+            .iter()
+            .filter_map(|definition| match definition {
+                Definition::Operation(operation) => Some(operation),
+                Definition::Fragment(_) => None,
+            })
+            .filter(|operation| {
+                let Some(operation_name) = &req.operation_name else {
+                    return true;
+                };
+                let name = match operation {
+                    OperationDefinition::SelectionSet(_) => None,
+                    OperationDefinition::Query(query) => query.name,
+                    OperationDefinition::Mutation(mutation) => mutation.name,
+                    OperationDefinition::Subscription(subscription) => subscription.name,
+                };
+                name == Some(operation_name.as_str())
+            })
+            .find_map(|operation| match operation {
+                OperationDefinition::Mutation(_) => Some("mutation"),
+                OperationDefinition::Subscription(_) => Some("subscription"),
+                _ => None,
+            })
+    }
 }
 
 #[cfg(feature = "graphql-client")]
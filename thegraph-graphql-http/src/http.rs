@@ -1,2 +1,5 @@
+#[cfg(any(feature = "reqwest", feature = "server", feature = "tower"))]
+pub(crate) mod content_type;
+pub mod incremental;
 pub mod request;
 pub mod response;
@@ -0,0 +1,91 @@
+//! Media type header values parsing.
+
+/// A parsed `Content-Type` header value, or a media range of an `Accept` header value.
+///
+/// See the section [8.3.1 Media Type](https://www.rfc-editor.org/rfc/rfc9110#section-8.3.1) of
+/// RFC 9110 for more information.
+#[derive(Debug, PartialEq, Eq)]
+pub(crate) struct ContentType<'a> {
+    /// The media type without parameters, e.g., `application/json`.
+    pub(crate) essence: &'a str,
+    /// The media type parameters, e.g., `charset=utf-8`.
+    params: &'a str,
+}
+
+impl<'a> ContentType<'a> {
+    /// Parse a `Content-Type` header value.
+    ///
+    /// The media type is returned as is, without its surrounding whitespace.
+    pub(crate) fn parse(value: &'a str) -> Self {
+        let (essence, params) = value.split_once(';').unwrap_or((value, ""));
+        Self {
+            essence: essence.trim(),
+            params,
+        }
+    }
+
+    /// Get the value of the given parameter, if present.
+    ///
+    /// Parameter names are matched case-insensitively. Quoted parameter values are unquoted.
+    pub(crate) fn param(&self, name: &str) -> Option<&'a str> {
+        self.params
+            .split(';')
+            .filter_map(|param| param.split_once('='))
+            .find(|(param, _)| param.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().trim_matches('"'))
+    }
+
+    /// Get the value of the `charset` parameter, if present.
+    pub(crate) fn charset(&self) -> Option<&'a str> {
+        self.param("charset")
+    }
+
+    /// Check if the media type essence matches the given media type, case-insensitively.
+    pub(crate) fn is(&self, media_type: &str) -> bool {
+        self.essence.eq_ignore_ascii_case(media_type)
+    }
+}
+
+/// Check if the given `charset` parameter value denotes the UTF-8 encoding.
+pub(crate) fn is_utf8_charset(charset: &str) -> bool {
+    charset.eq_ignore_ascii_case("utf-8") || charset.eq_ignore_ascii_case("utf8")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ContentType, is_utf8_charset};
+
+    #[test]
+    fn parse_content_type_without_parameters() {
+        //* When
+        let content_type = ContentType::parse("application/graphql-response+json");
+
+        //* Then
+        assert_eq!(content_type.essence, "application/graphql-response+json");
+        assert_eq!(content_type.charset(), None);
+    }
+
+    #[test]
+    fn parse_content_type_with_parameters() {
+        //* When
+        let content_type =
+            ContentType::parse("Application/GraphQL-Response+JSON ; foo=bar; Charset=\"UTF-8\"");
+
+        //* Then
+        assert!(content_type.is("application/graphql-response+json"));
+        assert_eq!(content_type.param("foo"), Some("bar"));
+        assert_eq!(content_type.charset(), Some("UTF-8"));
+        assert!(is_utf8_charset(content_type.charset().unwrap()));
+    }
+
+    #[test]
+    fn parse_content_type_with_other_charset() {
+        //* When
+        let content_type = ContentType::parse("application/json;charset=iso-8859-1");
+
+        //* Then
+        assert_eq!(content_type.essence, "application/json");
+        assert_eq!(content_type.charset(), Some("iso-8859-1"));
+        assert!(!is_utf8_charset(content_type.charset().unwrap()));
+    }
+}
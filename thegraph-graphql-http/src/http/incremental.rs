@@ -0,0 +1,391 @@
+//! Incremental delivery, i.e., `@defer` and `@stream`, response payloads.
+//!
+//! With incremental delivery, the GraphQL response is delivered as a `multipart/mixed` stream of
+//! payloads: an initial payload, with the data available upfront, followed by subsequent payloads
+//! carrying the deferred fragments and streamed list items. The `hasNext` entry of each payload
+//! indicates whether more payloads follow.
+//!
+//! See the [GraphQL incremental delivery RFC](https://github.com/graphql/graphql-spec/pull/742)
+//! for more information.
+
+use super::response::{Error, PathSegment, ResponseBody};
+
+/// The media type of incremental delivery GraphQL responses.
+pub const MULTIPART_MIXED_MEDIA_TYPE: &str = "multipart/mixed";
+
+/// The default boundary of incremental delivery `multipart/mixed` responses, used if the response
+/// `Content-Type` header does not carry the `boundary` parameter.
+pub const DEFAULT_MULTIPART_BOUNDARY: &str = "-";
+
+/// The initial payload of an incremental delivery GraphQL response.
+#[derive(Debug, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitialPayload<T> {
+    /// The data available upfront, i.e., without the deferred fragments and streamed list items.
+    pub data: Option<T>,
+
+    /// The errors raised while resolving the initial payload data.
+    #[serde(default)]
+    pub errors: Vec<Error>,
+
+    /// The initial payload extensions.
+    #[serde(default)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+
+    /// Whether more payloads follow.
+    #[serde(default)]
+    pub has_next: bool,
+}
+
+/// A subsequent payload of an incremental delivery GraphQL response.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SubsequentPayload {
+    /// The deferred fragments and streamed list items delivered by the payload.
+    #[serde(default)]
+    pub incremental: Vec<IncrementalResult>,
+
+    /// The subsequent payload extensions.
+    #[serde(default)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+
+    /// Whether more payloads follow.
+    #[serde(default)]
+    pub has_next: bool,
+}
+
+/// A deferred fragment, or a batch of streamed list items, delivered by a subsequent payload.
+#[derive(Debug, Clone, PartialEq, serde::Deserialize)]
+pub struct IncrementalResult {
+    /// The data of a deferred fragment, to be merged into the object at the `path`.
+    #[serde(default)]
+    pub data: Option<serde_json::Value>,
+
+    /// The streamed list items, to be inserted into the list starting at the index at the end of
+    /// the `path`.
+    #[serde(default)]
+    pub items: Option<Vec<serde_json::Value>>,
+
+    /// The path of the deferred fragment object, or of the first streamed list item.
+    #[serde(default)]
+    pub path: Vec<PathSegment>,
+
+    /// The label of the `@defer` or `@stream` directive, if any.
+    #[serde(default)]
+    pub label: Option<String>,
+
+    /// The errors raised while resolving the deferred fragment or the streamed list items.
+    #[serde(default)]
+    pub errors: Vec<Error>,
+
+    /// The incremental result extensions.
+    #[serde(default)]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
+}
+
+/// A payload of an incremental delivery GraphQL response.
+#[derive(Debug)]
+pub enum IncrementalPayload<T> {
+    /// The initial payload, with the data available upfront.
+    Initial(InitialPayload<T>),
+    /// A subsequent payload, with deferred fragments and streamed list items.
+    Subsequent(SubsequentPayload),
+}
+
+impl<T> IncrementalPayload<T> {
+    /// Whether more payloads follow.
+    pub fn has_next(&self) -> bool {
+        match self {
+            Self::Initial(payload) => payload.has_next,
+            Self::Subsequent(payload) => payload.has_next,
+        }
+    }
+}
+
+/// The error returned when an incremental result cannot be merged into the response data.
+#[derive(Debug, Clone, PartialEq, thiserror::Error)]
+pub enum MergeError {
+    /// The first payload is not an initial payload.
+    #[error("Missing initial payload")]
+    MissingInitialPayload,
+
+    /// An initial payload follows the first payload.
+    #[error("Unexpected initial payload")]
+    UnexpectedInitialPayload,
+
+    /// The incremental result path does not point to an object, or a list item, of the response
+    /// data.
+    #[error("Invalid incremental result path: {0:?}")]
+    InvalidPath(Vec<PathSegment>),
+}
+
+/// Merge the incremental result into the response data.
+///
+/// The data of a deferred fragment is deep-merged into the object at the result path. The streamed
+/// list items are inserted into the list at the result path parent, starting at the index at the
+/// end of the result path.
+pub fn apply_incremental_result(
+    data: &mut serde_json::Value,
+    result: &IncrementalResult,
+) -> Result<(), MergeError> {
+    let invalid_path = || MergeError::InvalidPath(result.path.clone());
+
+    if let Some(patch) = &result.data {
+        let target = value_at_path(data, &result.path).ok_or_else(invalid_path)?;
+        deep_merge(target, patch.clone());
+    }
+
+    if let Some(items) = &result.items {
+        let Some((PathSegment::Index(start), parent)) = result.path.split_last() else {
+            return Err(invalid_path());
+        };
+        let Some(serde_json::Value::Array(list)) = value_at_path(data, parent) else {
+            return Err(invalid_path());
+        };
+        for (index, item) in (*start..).zip(items) {
+            match index.cmp(&list.len()) {
+                std::cmp::Ordering::Less => list[index] = item.clone(),
+                std::cmp::Ordering::Equal => list.push(item.clone()),
+                std::cmp::Ordering::Greater => return Err(invalid_path()),
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Merge the payloads of an incremental delivery GraphQL response into the final response.
+///
+/// The errors and the extensions of all the payloads are collected into the final response. If
+/// an extension entry is present in several payloads, the last one is kept.
+pub fn merge_payloads(
+    payloads: impl IntoIterator<Item = IncrementalPayload<serde_json::Value>>,
+) -> Result<ResponseBody<serde_json::Value>, MergeError> {
+    let mut payloads = payloads.into_iter();
+    let Some(IncrementalPayload::Initial(initial)) = payloads.next() else {
+        return Err(MergeError::MissingInitialPayload);
+    };
+
+    let mut response = ResponseBody {
+        data: initial.data,
+        errors: initial.errors,
+        extensions: initial.extensions,
+    };
+    for payload in payloads {
+        let IncrementalPayload::Subsequent(payload) = payload else {
+            return Err(MergeError::UnexpectedInitialPayload);
+        };
+
+        for result in payload.incremental {
+            let data = response
+                .data
+                .as_mut()
+                .ok_or_else(|| MergeError::InvalidPath(result.path.clone()))?;
+            apply_incremental_result(data, &result)?;
+            response.errors.extend(result.errors);
+        }
+        response.extensions.extend(payload.extensions);
+    }
+
+    Ok(response)
+}
+
+/// Get the value at the given path, if any.
+fn value_at_path<'a>(
+    mut value: &'a mut serde_json::Value,
+    path: &[PathSegment],
+) -> Option<&'a mut serde_json::Value> {
+    for segment in path {
+        value = match (segment, value) {
+            (PathSegment::Field(field), serde_json::Value::Object(object)) => {
+                object.get_mut(field)?
+            }
+            (PathSegment::Index(index), serde_json::Value::Array(list)) => list.get_mut(*index)?,
+            _ => return None,
+        };
+    }
+    Some(value)
+}
+
+/// Deep-merge the patch into the target value. Objects are merged recursively, any other value
+/// is replaced.
+fn deep_merge(target: &mut serde_json::Value, patch: serde_json::Value) {
+    match (target, patch) {
+        (serde_json::Value::Object(target), serde_json::Value::Object(patch)) => {
+            for (key, value) in patch {
+                match target.get_mut(&key) {
+                    Some(target) => deep_merge(target, value),
+                    None => {
+                        target.insert(key, value);
+                    }
+                }
+            }
+        }
+        (target, patch) => *target = patch,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{
+        IncrementalPayload, IncrementalResult, InitialPayload, MergeError, SubsequentPayload,
+        apply_incremental_result, merge_payloads,
+    };
+
+    /// Deserialize the given JSON value as an incremental result.
+    fn incremental_result(value: serde_json::Value) -> IncrementalResult {
+        serde_json::from_value(value).expect("incremental result to be valid")
+    }
+
+    #[test]
+    fn merge_deferred_fragment() {
+        //* Given
+        let mut data = serde_json::json!({
+            "indexer": { "id": "0x01", "allocations": [{ "id": "0xa" }] }
+        });
+        let result = incremental_result(serde_json::json!({
+            "data": { "url": "https://indexer.example", "allocations": [{ "id": "0xb" }] },
+            "path": ["indexer"],
+            "label": "details"
+        }));
+
+        //* When
+        let merged = apply_incremental_result(&mut data, &result);
+
+        //* Then
+        assert_matches!(merged, Ok(()));
+        assert_eq!(
+            data,
+            serde_json::json!({
+                "indexer": {
+                    "id": "0x01",
+                    "url": "https://indexer.example",
+                    "allocations": [{ "id": "0xb" }]
+                }
+            })
+        );
+    }
+
+    #[test]
+    fn merge_streamed_list_items() {
+        //* Given
+        let mut data = serde_json::json!({ "indexers": [{ "id": "0x01" }] });
+        let result = incremental_result(serde_json::json!({
+            "items": [{ "id": "0x02" }, { "id": "0x03" }],
+            "path": ["indexers", 1]
+        }));
+
+        //* When
+        let merged = apply_incremental_result(&mut data, &result);
+
+        //* Then
+        assert_matches!(merged, Ok(()));
+        assert_eq!(
+            data,
+            serde_json::json!({ "indexers": [{ "id": "0x01" }, { "id": "0x02" }, { "id": "0x03" }] })
+        );
+    }
+
+    #[test]
+    fn fail_merge_with_invalid_path() {
+        //* Given
+        let mut data = serde_json::json!({ "indexers": [{ "id": "0x01" }] });
+        let unknown_field = incremental_result(serde_json::json!({
+            "data": { "url": "https://indexer.example" },
+            "path": ["indexer"]
+        }));
+        let items_gap = incremental_result(serde_json::json!({
+            "items": [{ "id": "0x03" }],
+            "path": ["indexers", 2]
+        }));
+
+        //* Then
+        assert_matches!(
+            apply_incremental_result(&mut data, &unknown_field),
+            Err(MergeError::InvalidPath(path)) => {
+                assert_eq!(path, vec!["indexer".into()]);
+            }
+        );
+        assert_matches!(
+            apply_incremental_result(&mut data, &items_gap),
+            Err(MergeError::InvalidPath(_))
+        );
+    }
+
+    #[test]
+    fn merge_all_payloads() {
+        //* Given
+        let payloads = vec![
+            IncrementalPayload::Initial(InitialPayload {
+                data: Some(serde_json::json!({ "indexer": { "id": "0x01" } })),
+                errors: vec![],
+                extensions: Default::default(),
+                has_next: true,
+            }),
+            IncrementalPayload::Subsequent(
+                serde_json::from_value::<SubsequentPayload>(serde_json::json!({
+                    "incremental": [{
+                        "data": { "url": null },
+                        "path": ["indexer"],
+                        "errors": [{ "message": "url unavailable", "path": ["indexer", "url"] }]
+                    }],
+                    "extensions": { "cost": 1 },
+                    "hasNext": false
+                }))
+                .expect("subsequent payload to be valid"),
+            ),
+        ];
+
+        //* When
+        let response = merge_payloads(payloads);
+
+        //* Then
+        assert_matches!(response, Ok(response) => {
+            assert_eq!(
+                response.data,
+                Some(serde_json::json!({ "indexer": { "id": "0x01", "url": null } }))
+            );
+            assert_eq!(response.errors.len(), 1);
+            assert_eq!(response.errors[0].message, "url unavailable");
+            assert_eq!(response.extensions["cost"], 1);
+        });
+    }
+
+    #[test]
+    fn fail_merge_without_initial_payload() {
+        //* Given
+        let payloads = vec![IncrementalPayload::Subsequent(SubsequentPayload {
+            incremental: vec![],
+            extensions: Default::default(),
+            has_next: false,
+        })];
+
+        //* Then
+        assert_matches!(
+            merge_payloads(payloads),
+            Err(MergeError::MissingInitialPayload)
+        );
+    }
+
+    #[test]
+    fn fail_merge_with_several_initial_payloads() {
+        //* Given
+        let initial = || {
+            IncrementalPayload::Initial(InitialPayload {
+                data: Some(serde_json::json!({ "indexer": { "id": "0x01" } })),
+                errors: vec![],
+                extensions: Default::default(),
+                has_next: true,
+            })
+        };
+        let payloads = vec![initial(), initial()];
+
+        //* Then
+        assert_matches!(
+            merge_payloads(payloads),
+            Err(MergeError::UnexpectedInitialPayload)
+        );
+    }
+}
@@ -28,6 +28,105 @@ pub struct RequestParameters {
     pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
+/// The error returned when the parameters of a GraphQL-over-HTTP request are not valid.
+///
+/// See the section [5.1 Request Parameters](https://graphql.github.io/graphql-over-http/draft/#sec-Request-Parameters)
+/// of the GraphQL-over-HTTP specification.
+#[derive(Debug, thiserror::Error)]
+pub enum InvalidRequestParameters {
+    /// The `query` parameter is missing.
+    #[error("The `query` parameter is missing")]
+    MissingQuery,
+
+    /// The `query` parameter is not a string.
+    #[error("The `query` parameter must be a string")]
+    InvalidQuery,
+
+    /// The `operationName` parameter is not a string.
+    #[error("The `operationName` parameter must be a string")]
+    InvalidOperationName,
+
+    /// The `variables` parameter is not a map.
+    #[error("The `variables` parameter must be a map")]
+    InvalidVariables,
+
+    /// The `extensions` parameter is not a map.
+    #[error("The `extensions` parameter must be a map")]
+    InvalidExtensions,
+}
+
+impl RequestParameters {
+    /// Create the request parameters from the raw request parameter values, validating them.
+    ///
+    /// A `null` value is treated as if the parameter was not present.
+    pub(crate) fn from_values(
+        query: Option<serde_json::Value>,
+        operation_name: Option<serde_json::Value>,
+        variables: Option<serde_json::Value>,
+        extensions: Option<serde_json::Value>,
+    ) -> Result<Self, InvalidRequestParameters> {
+        use serde_json::Value;
+
+        // > A Document containing GraphQL Operations and Fragments to execute. It MUST be a string.
+        let query = match query {
+            Some(Value::String(query)) => Document::new(query),
+            None | Some(Value::Null) => return Err(InvalidRequestParameters::MissingQuery),
+            Some(_) => return Err(InvalidRequestParameters::InvalidQuery),
+        };
+
+        // > The name of the Operation in the Document to execute. If present, it MUST be a string.
+        let operation_name = match operation_name {
+            Some(Value::String(operation_name)) => Some(operation_name),
+            None | Some(Value::Null) => None,
+            Some(_) => return Err(InvalidRequestParameters::InvalidOperationName),
+        };
+
+        // > Values for any Variables defined by the Operation. If present, it MUST be a map.
+        let variables = match variables {
+            Some(Value::Object(variables)) => variables,
+            None | Some(Value::Null) => Default::default(),
+            Some(_) => return Err(InvalidRequestParameters::InvalidVariables),
+        };
+
+        // > This entry is reserved for implementors to extend the protocol however they see fit. If
+        // > present, it MUST be a map.
+        let extensions = match extensions {
+            Some(Value::Object(extensions)) => extensions,
+            None | Some(Value::Null) => Default::default(),
+            Some(_) => return Err(InvalidRequestParameters::InvalidExtensions),
+        };
+
+        Ok(Self {
+            query,
+            operation_name,
+            variables,
+            extensions,
+        })
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for RequestParameters {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// The raw request parameters, validated after deserialization. Unknown entries are
+        /// ignored.
+        #[derive(serde::Deserialize)]
+        struct RawRequestParameters {
+            #[serde(default)]
+            query: Option<serde_json::Value>,
+            #[serde(default, rename = "operationName")]
+            operation_name: Option<serde_json::Value>,
+            #[serde(default)]
+            variables: Option<serde_json::Value>,
+            #[serde(default)]
+            extensions: Option<serde_json::Value>,
+        }
+
+        let raw = RawRequestParameters::deserialize(deserializer)?;
+        Self::from_values(raw.query, raw.operation_name, raw.variables, raw.extensions)
+            .map_err(serde::de::Error::custom)
+    }
+}
+
 /// Convert `self` into a `RequestParameters` struct.
 pub trait IntoRequestParameters {
     /// Consumes `self` and returns a `RequestParameters` struct.
@@ -65,3 +164,98 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::RequestParameters;
+
+    #[test]
+    fn deserialize_request_parameters() {
+        //* Given
+        let body = r#"{
+            "query": "query Film($id: ID) { film(id: $id) { title } }",
+            "operationName": "Film",
+            "variables": {"id": "1"},
+            "extensions": {"foo": "bar"},
+            "unknown": true
+        }"#;
+
+        //* When
+        let params = serde_json::from_str::<RequestParameters>(body);
+
+        //* Then
+        assert_matches!(params, Ok(params) => {
+            assert_eq!(
+                params.query.as_str(),
+                "query Film($id: ID) { film(id: $id) { title } }"
+            );
+            assert_eq!(params.operation_name.as_deref(), Some("Film"));
+            assert_eq!(params.variables["id"], "1");
+            assert_eq!(params.extensions["foo"], "bar");
+        });
+    }
+
+    #[test]
+    fn deserialize_request_parameters_with_null_optional_entries() {
+        //* Given
+        let body = r#"{"query": "{ a }", "operationName": null, "variables": null}"#;
+
+        //* When
+        let params = serde_json::from_str::<RequestParameters>(body);
+
+        //* Then
+        assert_matches!(params, Ok(params) => {
+            assert_eq!(params.operation_name, None);
+            assert!(params.variables.is_empty());
+        });
+    }
+
+    #[test]
+    fn serialize_deserialize_round_trip() {
+        //* Given
+        let params = RequestParameters {
+            query: "{ a }".to_string().into(),
+            operation_name: Some("A".to_string()),
+            variables: serde_json::json!({"id": 1}).as_object().unwrap().clone(),
+            extensions: Default::default(),
+        };
+
+        //* When
+        let body = serde_json::to_string(&params).unwrap();
+
+        //* Then
+        assert_matches!(serde_json::from_str::<RequestParameters>(&body), Ok(decoded) => {
+            assert_eq!(decoded, params);
+        });
+    }
+
+    #[test]
+    fn fail_deserialize_invalid_request_parameters() {
+        //* Given
+        let invalid = [
+            (r#"{}"#, "`query` parameter is missing"),
+            (r#"{"query": 42}"#, "`query` parameter must be a string"),
+            (
+                r#"{"query": "{ a }", "operationName": 1}"#,
+                "`operationName` parameter must be a string",
+            ),
+            (
+                r#"{"query": "{ a }", "variables": "{}"}"#,
+                "`variables` parameter must be a map",
+            ),
+            (
+                r#"{"query": "{ a }", "extensions": []}"#,
+                "`extensions` parameter must be a map",
+            ),
+        ];
+
+        //* Then
+        for (body, message) in invalid {
+            assert_matches!(serde_json::from_str::<RequestParameters>(body), Err(err) => {
+                assert!(err.to_string().contains(message), "{body}: {err}");
+            });
+        }
+    }
+}
@@ -12,7 +12,7 @@ pub const GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE: &str = "application/json";
 /// [7.1.2 Errors](https://spec.graphql.org/draft/#sec-Errors) and the
 /// [Error Result Format](https://spec.graphql.org/draft/#sec-Errors.Error-Result-Format) subsection
 /// of the GraphQL specification.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, serde::Deserialize, serde::Serialize)]
 pub struct Error {
     /// A short, human-readable description of the problem.
     ///
@@ -55,7 +55,20 @@ pub struct Error {
     /// > aliased name, since it represents a path in the response, not in the request.
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
-    pub path: Vec<String>,
+    pub path: Vec<PathSegment>,
+
+    /// A map of additional error information, e.g., the error `code`.
+    ///
+    /// From the [Error Result Format](https://spec.graphql.org/draft/#sec-Errors.Error-Result-Format)
+    /// subsection of the GraphQL specification:
+    ///
+    /// > GraphQL services may provide an additional entry to errors with key `extensions`. This
+    /// > entry, if set, must have a map as its value. This entry is reserved for implementers to
+    /// > add additional information to errors however they see fit, and there are no additional
+    /// > restrictions on its contents.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 impl Error {
@@ -65,10 +78,151 @@ impl Error {
             message: message.to_string(),
             locations: vec![],
             path: vec![],
+            extensions: Default::default(),
+        }
+    }
+
+    /// Set the error `code` entry of the error's `extensions` map.
+    pub fn with_code(mut self, code: ErrorCode) -> Self {
+        self.extensions.insert(
+            ERROR_CODE_EXTENSION.to_string(),
+            serde_json::Value::String(code.as_str().to_string()),
+        );
+        self
+    }
+
+    /// Get the error code from the `code` entry of the error's `extensions` map.
+    ///
+    /// Returns `None` if the entry is not present or is not a string.
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.extensions
+            .get(ERROR_CODE_EXTENSION)
+            .and_then(serde_json::Value::as_str)
+            .map(ErrorCode::from)
+    }
+}
+
+/// A segment of an error's path, a field name or a list index.
+///
+/// Fields are serialized as strings and list indices as 0-indexed integers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, serde::Deserialize, serde::Serialize)]
+#[serde(untagged)]
+pub enum PathSegment {
+    /// A response field name, or its alias.
+    Field(String),
+    /// A 0-indexed list index.
+    Index(usize),
+}
+
+impl From<&str> for PathSegment {
+    fn from(field: &str) -> Self {
+        Self::Field(field.to_string())
+    }
+}
+
+impl From<String> for PathSegment {
+    fn from(field: String) -> Self {
+        Self::Field(field)
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(index: usize) -> Self {
+        Self::Index(index)
+    }
+}
+
+impl std::fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Field(field) => f.write_str(field),
+            Self::Index(index) => write!(f, "{index}"),
+        }
+    }
+}
+
+/// The key of the error code entry in an error's `extensions` map.
+const ERROR_CODE_EXTENSION: &str = "code";
+
+/// Well-known error codes, set in the `code` entry of an error's `extensions` map by GraphQL
+/// servers, _The Graph_ gateways and indexers.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ErrorCode {
+    /// The GraphQL document could not be parsed.
+    GraphqlParseFailed,
+    /// The GraphQL document is not valid against the schema.
+    GraphqlValidationFailed,
+    /// The request contains invalid input, e.g., the variables.
+    BadUserInput,
+    /// The persisted query is unknown to the server.
+    PersistedQueryNotFound,
+    /// The server does not support persisted queries.
+    PersistedQueryNotSupported,
+    /// The server failed to process the request.
+    InternalServerError,
+    /// The subgraph deployment is unknown, or not indexed.
+    DeploymentNotFound,
+    /// The subgraph is unknown.
+    SubgraphNotFound,
+    /// The requested block is unknown, or not yet indexed.
+    BlockNotFound,
+    /// No indexer is available to serve the request.
+    IndexersUnavailable,
+    /// The client exceeded its rate limit.
+    RateLimited,
+    /// The client is not authorized, e.g., its API key is missing or invalid.
+    Unauthorized,
+    /// Any other error code.
+    Other(String),
+}
+
+impl ErrorCode {
+    /// Get the error code string.
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::GraphqlParseFailed => "GRAPHQL_PARSE_FAILED",
+            Self::GraphqlValidationFailed => "GRAPHQL_VALIDATION_FAILED",
+            Self::BadUserInput => "BAD_USER_INPUT",
+            Self::PersistedQueryNotFound => "PERSISTED_QUERY_NOT_FOUND",
+            Self::PersistedQueryNotSupported => "PERSISTED_QUERY_NOT_SUPPORTED",
+            Self::InternalServerError => "INTERNAL_SERVER_ERROR",
+            Self::DeploymentNotFound => "DEPLOYMENT_NOT_FOUND",
+            Self::SubgraphNotFound => "SUBGRAPH_NOT_FOUND",
+            Self::BlockNotFound => "BLOCK_NOT_FOUND",
+            Self::IndexersUnavailable => "INDEXERS_UNAVAILABLE",
+            Self::RateLimited => "RATE_LIMITED",
+            Self::Unauthorized => "UNAUTHORIZED",
+            Self::Other(code) => code,
+        }
+    }
+}
+
+impl From<&str> for ErrorCode {
+    fn from(code: &str) -> Self {
+        match code {
+            "GRAPHQL_PARSE_FAILED" => Self::GraphqlParseFailed,
+            "GRAPHQL_VALIDATION_FAILED" => Self::GraphqlValidationFailed,
+            "BAD_USER_INPUT" => Self::BadUserInput,
+            "PERSISTED_QUERY_NOT_FOUND" => Self::PersistedQueryNotFound,
+            "PERSISTED_QUERY_NOT_SUPPORTED" => Self::PersistedQueryNotSupported,
+            "INTERNAL_SERVER_ERROR" => Self::InternalServerError,
+            "DEPLOYMENT_NOT_FOUND" => Self::DeploymentNotFound,
+            "SUBGRAPH_NOT_FOUND" => Self::SubgraphNotFound,
+            "BLOCK_NOT_FOUND" => Self::BlockNotFound,
+            "INDEXERS_UNAVAILABLE" => Self::IndexersUnavailable,
+            "RATE_LIMITED" => Self::RateLimited,
+            "UNAUTHORIZED" => Self::Unauthorized,
+            code => Self::Other(code.to_string()),
         }
     }
 }
 
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// A trait for types that can be converted into [`Error`], a GraphQL HTTP Response error.
 pub trait IntoError {
     /// Convert the type into [`Error`].
@@ -91,12 +245,13 @@ where
             message: self.to_string(),
             locations: vec![],
             path: vec![],
+            extensions: Default::default(),
         }
     }
 }
 
 /// A location describing the beginning of the associated syntax element causing the error.
-#[derive(Debug, serde::Deserialize, serde::Serialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
 pub struct ErrorLocation {
     pub line: usize,
     pub column: usize,
@@ -126,6 +281,15 @@ pub struct ResponseBody<T> {
     #[serde(default)]
     #[serde(skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<Error>,
+
+    /// Reserved for implementors to extend the protocol however they see fit, e.g., with tracing
+    /// or cost information.
+    ///
+    /// As specified in the section [7. Response](https://spec.graphql.org/draft/#sec-Response) of
+    /// the GraphQL specification.
+    #[serde(default)]
+    #[serde(skip_serializing_if = "serde_json::Map::is_empty")]
+    pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 impl<T> ResponseBody<T> {
@@ -134,6 +298,7 @@ impl<T> ResponseBody<T> {
         Self {
             data: Some(data),
             errors: vec![],
+            extensions: Default::default(),
         }
     }
 
@@ -142,6 +307,7 @@ impl<T> ResponseBody<T> {
         Self {
             data: None,
             errors: vec![error.into_error()],
+            extensions: Default::default(),
         }
     }
 }
@@ -150,7 +316,7 @@ impl<T> ResponseBody<T> {
 mod tests {
     use assert_matches::assert_matches;
 
-    use super::{Error, IntoError, ResponseBody};
+    use super::{Error, ErrorCode, IntoError, PathSegment, ResponseBody};
 
     /// Deserialize the given string as a GraphQL response body.
     fn deserialize_response_body<T>(response_body: &str) -> serde_json::Result<ResponseBody<T>>
@@ -280,4 +446,80 @@ mod tests {
             assert_eq!(resp_body.errors[0].message, "test error: test message");
         });
     }
+
+    /// Ensure that errors with mixed field and list index path segments, and extensions, are
+    /// correctly deserialized.
+    #[test]
+    fn deserialize_error_with_mixed_path_and_extensions() {
+        //* Given
+        let response_body = r#"{
+            "data": {"items": [null]},
+            "errors": [{
+                "message": "Field error",
+                "locations": [{"line": 1, "column": 10}],
+                "path": ["items", 0, "name"],
+                "extensions": {"code": "BLOCK_NOT_FOUND", "block": 42}
+            }]
+        }"#;
+
+        //* When
+        let response_body = deserialize_response_body::<serde_json::Value>(response_body);
+
+        //* Then
+        assert_matches!(response_body, Ok(resp_body) => {
+            assert_eq!(resp_body.errors.len(), 1);
+
+            let error = &resp_body.errors[0];
+            assert_eq!(
+                error.path,
+                [
+                    PathSegment::from("items"),
+                    PathSegment::from(0),
+                    PathSegment::from("name")
+                ]
+            );
+            assert_eq!(error.extensions["block"], 42);
+            assert_eq!(error.code(), Some(ErrorCode::BlockNotFound));
+        });
+    }
+
+    /// Ensure that errors are serialized in the same format as before the path segments and the
+    /// extensions were supported.
+    #[test]
+    fn serialize_error_backward_compatible() {
+        //* Given
+        let mut error = Error::from_static("test error message");
+        error.path = vec!["field".into(), "nested".into()];
+
+        //* When
+        let error = serde_json::to_string(&error).unwrap();
+
+        //* Then
+        assert_eq!(
+            error,
+            r#"{"message":"test error message","path":["field","nested"]}"#
+        );
+    }
+
+    /// Ensure that the error code is set in, and read from, the extensions map.
+    #[test]
+    fn error_code_round_trip() {
+        //* Given
+        let error = Error::from_static("test error message").with_code(ErrorCode::RateLimited);
+        let other =
+            Error::from_static("test error message").with_code(ErrorCode::from("SOMETHING_ELSE"));
+
+        //* When
+        let error = serde_json::to_value(&error).unwrap();
+
+        //* Then
+        assert_eq!(error["extensions"]["code"], "RATE_LIMITED");
+        assert_matches!(serde_json::from_value::<Error>(error), Ok(error) => {
+            assert_eq!(error.code(), Some(ErrorCode::RateLimited));
+        });
+        assert_eq!(
+            other.code(),
+            Some(ErrorCode::Other("SOMETHING_ELSE".to_string()))
+        );
+    }
 }
@@ -1,27 +1,55 @@
 //! HTTP client extensions.
 
+use http::{
+    HeaderMap, HeaderValue, StatusCode,
+    header::{ACCEPT, CONTENT_TYPE},
+};
+
 #[cfg(feature = "reqwest")]
 #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
-pub use reqwest_ext::ReqwestExt;
-
-use crate::http::response::{Error, ResponseBody};
+pub use self::reqwest_ext::ReqwestExt;
+#[cfg(feature = "tower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+pub use self::transport::ServiceTransport;
+pub use self::transport::{GraphQlClient, GraphQlTransport};
+use crate::http::{
+    content_type::{ContentType, is_utf8_charset},
+    request::{GRAPHQL_REQUEST_MEDIA_TYPE, RequestParameters},
+    response::{
+        Error, ErrorCode, GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE, GRAPHQL_RESPONSE_MEDIA_TYPE,
+        ResponseBody,
+    },
+};
 
-/// The error type returned by `ReqwestExt`
 #[cfg(feature = "reqwest")]
-#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+mod multipart;
+mod transport;
+
+/// The error type returned by `ReqwestExt` and the `GraphQlClient`
+///
+/// The set of variants depends on the enabled features, e.g., the `RequestSendError` variant
+/// requires the `reqwest` feature.
 #[derive(thiserror::Error, Debug)]
+#[non_exhaustive]
 pub enum RequestError {
     /// An error occurred while serializing the GraphQL request.
     #[error("Error serializing GraphQL request parameters: {0}")]
     RequestSerializationError(serde_json::Error),
 
     /// An error occurred while making the HTTP request.
+    #[cfg(feature = "reqwest")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
     #[error("Error making HTTP request: {0}")]
     RequestSendError(#[from] reqwest::Error),
 
+    /// An error occurred while sending the HTTP request, or receiving the HTTP response, using a
+    /// [`GraphQlTransport`].
+    #[error("Error sending HTTP request: {0}")]
+    TransportError(Box<dyn std::error::Error + Send + Sync>),
+
     /// An error occurred while receiving the HTTP response.
     #[error("Error receiving HTTP response ({0}): {1}")]
-    ResponseRecvError(reqwest::StatusCode, String),
+    ResponseRecvError(http::StatusCode, String),
 
     /// An error occurred while deserializing the GraphQL response.
     #[error(
@@ -31,6 +59,67 @@ pub enum RequestError {
         error: serde_json::Error,
         response: String,
     },
+
+    /// The GraphQL operation cannot be sent using the `GET` method, e.g., a mutation.
+    #[error("GraphQL {0} operations cannot be sent using the GET method")]
+    OperationNotAllowedOverGet(&'static str),
+
+    /// The server does not support batched GraphQL requests, i.e., it did not reply with a JSON
+    /// array of GraphQL responses.
+    #[error("GraphQL request batching not supported by the server ({0}): {1}")]
+    BatchNotSupported(http::StatusCode, String),
+
+    /// The number of GraphQL responses in the batch response does not match the number of
+    /// GraphQL requests in the batch.
+    #[error("GraphQL batch response mismatch: expected {expected} responses, received {received}")]
+    BatchResponseMismatch { expected: usize, received: usize },
+}
+
+/// The default maximum URL length of GraphQL-over-HTTP `GET` requests.
+pub const DEFAULT_MAX_GET_URL_LENGTH: usize = 2048;
+
+/// The options of GraphQL-over-HTTP `GET` requests. See [`GraphQlClient::send_graphql_get`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GetRequestOptions {
+    /// The maximum length of the request URL, including the GraphQL request parameters. Requests
+    /// exceeding it are sent using the `POST` method instead.
+    pub max_url_length: usize,
+}
+
+impl Default for GetRequestOptions {
+    fn default() -> Self {
+        Self {
+            max_url_length: DEFAULT_MAX_GET_URL_LENGTH,
+        }
+    }
+}
+
+/// A GraphQL-over-HTTP response envelope, retaining the HTTP response metadata and the raw response
+/// body bytes.
+///
+/// The raw body is needed, e.g., to verify the response attestation, which is computed over the
+/// exact response string. Use [`ResponseEnvelope::decode`] to deserialize the GraphQL response
+/// borrowing from the retained bytes.
+#[derive(Debug, Clone)]
+pub struct ResponseEnvelope {
+    /// The HTTP response status code.
+    pub status: http::StatusCode,
+
+    /// The HTTP response headers.
+    pub headers: http::HeaderMap,
+
+    /// The raw HTTP response body.
+    pub body: bytes::Bytes,
+}
+
+/// A decoded GraphQL-over-HTTP response. See [`ResponseEnvelope::decode`].
+#[derive(Debug)]
+pub struct DecodedResponse<ResponseData> {
+    /// The GraphQL response result, keeping the data of partial responses.
+    pub result: ResponseResult<PartialResponse<ResponseData>>,
+
+    /// The top-level `extensions` entry of the GraphQL response.
+    pub extensions: serde_json::Map<String, serde_json::Value>,
 }
 
 /// The possible errors results of a GraphQL-over-HTTP response.
@@ -48,16 +137,70 @@ pub enum ResponseError {
     },
 }
 
+/// The maximum size of an incremental delivery response part, i.e., a payload along with its part
+/// headers. See [`ReqwestExt::send_graphql_incremental`].
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+pub const MAX_INCREMENTAL_PART_SIZE: usize = 10 * 1024 * 1024;
+
+/// A stream of incremental delivery GraphQL response payloads. See
+/// [`ReqwestExt::send_graphql_incremental`].
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+pub type IncrementalStream<ResponseData> = std::pin::Pin<
+    Box<
+        dyn futures_util::Stream<
+                Item = Result<
+                    crate::http::incremental::IncrementalPayload<ResponseData>,
+                    RequestError,
+                >,
+            > + Send,
+    >,
+>;
+
 /// The result type of GraphQL-over-HTTP request.
 pub type ResponseResult<ResponseData> = Result<ResponseData, ResponseError>;
 
-/// Process the GraphQL response body.
+/// A GraphQL response carrying data, possibly along with the _field errors_ raised during
+/// execution.
+///
+/// As specified in the section [7.1.2 Errors](https://spec.graphql.org/draft/#sec-Errors) of the
+/// GraphQL specification, a field error on a nullable field resolves the field to `null` and the
+/// rest of the data is still returned. A partial response keeps that data instead of discarding it.
+#[derive(Debug)]
+pub struct PartialResponse<ResponseData> {
+    /// The response data.
+    pub data: ResponseData,
+
+    /// The field errors raised during execution. Empty if the response is complete.
+    pub errors: Vec<Error>,
+}
+
+impl<ResponseData> PartialResponse<ResponseData> {
+    /// Check if the response is complete, i.e., no field errors were raised during execution.
+    pub fn is_complete(&self) -> bool {
+        self.errors.is_empty()
+    }
+
+    /// Convert the partial response into a strict response result.
+    ///
+    /// If any field error was raised during execution, the data is discarded and a
+    /// [`ResponseError::Failure`] is returned.
+    pub fn into_result(self) -> ResponseResult<ResponseData> {
+        if self.errors.is_empty() {
+            Ok(self.data)
+        } else {
+            Err(ResponseError::Failure {
+                errors: self.errors,
+            })
+        }
+    }
+}
+
+/// Process the GraphQL response body, keeping the data of partial responses.
 fn process_response_body<ResponseData>(
     resp: ResponseBody<ResponseData>,
-) -> ResponseResult<ResponseData>
-where
-    ResponseData: serde::de::DeserializeOwned,
-{
+) -> ResponseResult<PartialResponse<ResponseData>> {
     // [7.1.2 Errors](https://spec.graphql.org/draft/#sec-Errors)
     //
     // > If present, the `errors` entry in the response must contain at least one error. If no
@@ -71,25 +214,434 @@ where
     // > `errors` entry MUST be present if and only if one or more _field error_ was raised during
     // > execution.
     match (resp.data, resp.errors) {
-        (Some(data), errors) if errors.is_empty() => Ok(data),
+        (Some(data), errors) => Ok(PartialResponse { data, errors }),
         (None, errors) if errors.is_empty() => Err(ResponseError::Empty),
-        // Do not consider partial responses
-        (_, errors) => Err(ResponseError::Failure { errors }),
+        (None, errors) => Err(ResponseError::Failure { errors }),
+    }
+}
+
+/// The `Accept` header value, supporting both the legacy and the current GraphQL-over-HTTP
+/// media types.
+///
+/// See the section [5.2.1 Legacy Watershed](https://graphql.github.io/graphql-over-http/draft/#sec-Legacy-Watershed)
+/// of the GraphQL-over-HTTP specification.
+fn accept_header_value() -> HeaderValue {
+    let value = format!(
+        "{GRAPHQL_RESPONSE_MEDIA_TYPE}; charset=utf-8, {GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE}; charset=utf-8"
+    );
+    HeaderValue::from_str(&value).expect("header to be valid ascii")
+}
+
+/// The headers of a GraphQL-over-HTTP `POST` request, accepting the given response media types.
+fn graphql_post_headers(accept: HeaderValue) -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    // Set `Content-Type` header to `application/json` as specified in the section
+    // [5.4 POST](https://graphql.github.io/graphql-over-http/draft/#sec-POST) of the
+    // GraphQL-over-HTTP specification.
+    headers.insert(
+        CONTENT_TYPE,
+        HeaderValue::from_static(GRAPHQL_REQUEST_MEDIA_TYPE),
+    );
+    headers.insert(ACCEPT, accept);
+    headers
+}
+
+/// Build a GraphQL-over-HTTP `POST` request to the given URL, with the given serialized GraphQL
+/// request as the request body.
+///
+/// The `Content-Type` header is set to the GraphQL-over-HTTP request media type, and the `Accept`
+/// header to the given value.
+fn graphql_post_request(
+    url: &http::Uri,
+    accept: HeaderValue,
+    body: Vec<u8>,
+) -> http::Request<bytes::Bytes> {
+    let mut request = http::Request::new(bytes::Bytes::from(body));
+    *request.method_mut() = http::Method::POST;
+    *request.uri_mut() = url.clone();
+    *request.headers_mut() = graphql_post_headers(accept);
+    request
+}
+
+/// Build a GraphQL-over-HTTP `GET` request to the given URL, with the GraphQL request parameters
+/// serialized into the URL query string.
+///
+/// As specified in the GraphQL-over-HTTP specification, only query operations can be sent using
+/// the `GET` method. With the `graphql-parser` feature enabled, other operations, e.g., mutations,
+/// are rejected. If the resulting URL is longer than [`GetRequestOptions::max_url_length`], a
+/// `POST` request is built instead.
+fn graphql_get_request(
+    url: &http::Uri,
+    gql_request: &RequestParameters,
+    options: GetRequestOptions,
+) -> Result<http::Request<bytes::Bytes>, RequestError> {
+    // [6.2.1 GET](https://graphql.github.io/graphql-over-http/draft/#sec-GET)
+    //
+    // > GET requests MUST NOT be used for executing mutation operations.
+    #[cfg(feature = "graphql-parser")]
+    if let Some(operation) = crate::compat::compat_graphql_parser::non_query_operation(gql_request)
+    {
+        return Err(RequestError::OperationNotAllowedOverGet(operation));
+    }
+
+    // Serialize the GraphQL request parameters into the URL query string, after the URL query
+    // parameters, if any. The `variables` and `extensions` parameters are JSON-encoded.
+    let encode_json = |map: &serde_json::Map<String, serde_json::Value>| {
+        (!map.is_empty())
+            .then(|| serde_json::to_string(map))
+            .transpose()
+            .map_err(RequestError::RequestSerializationError)
+    };
+    let variables = encode_json(&gql_request.variables)?;
+    let extensions = encode_json(&gql_request.extensions)?;
+
+    let mut query = form_urlencoded::Serializer::new(url.query().unwrap_or_default().to_string());
+    query.append_pair("query", gql_request.query.as_str());
+    if let Some(operation_name) = &gql_request.operation_name {
+        query.append_pair("operationName", operation_name);
+    }
+    if let Some(variables) = &variables {
+        query.append_pair("variables", variables);
+    }
+    if let Some(extensions) = &extensions {
+        query.append_pair("extensions", extensions);
+    }
+
+    let mut parts = url.clone().into_parts();
+    parts.path_and_query = Some(
+        format!("{}?{}", url.path(), query.finish())
+            .try_into()
+            .map_err(|err: http::uri::InvalidUri| RequestError::TransportError(err.into()))?,
+    );
+    let get_url =
+        http::Uri::from_parts(parts).map_err(|err| RequestError::TransportError(err.into()))?;
+
+    // Fall back to the `POST` method if the URL is too long
+    if get_url.to_string().len() > options.max_url_length {
+        let gql_request_body =
+            serde_json::to_vec(gql_request).map_err(RequestError::RequestSerializationError)?;
+        return Ok(graphql_post_request(
+            url,
+            accept_header_value(),
+            gql_request_body,
+        ));
+    }
+
+    let mut request = http::Request::new(bytes::Bytes::new());
+    *request.method_mut() = http::Method::GET;
+    *request.uri_mut() = get_url;
+    request.headers_mut().insert(ACCEPT, accept_header_value());
+    Ok(request)
+}
+
+/// Get the automatic persisted queries error code of a failed GraphQL response, if any.
+///
+/// Some servers do not set the error code, and only report it in the error message.
+fn persisted_query_error<ResponseData>(result: &ResponseResult<ResponseData>) -> Option<ErrorCode> {
+    let Err(ResponseError::Failure { errors }) = result else {
+        return None;
+    };
+
+    errors.iter().find_map(|error| match error.code() {
+        Some(
+            code @ (ErrorCode::PersistedQueryNotFound | ErrorCode::PersistedQueryNotSupported),
+        ) => Some(code),
+        _ => match error.message.as_str() {
+            "PersistedQueryNotFound" => Some(ErrorCode::PersistedQueryNotFound),
+            "PersistedQueryNotSupported" => Some(ErrorCode::PersistedQueryNotSupported),
+            _ => None,
+        },
+    })
+}
+
+impl ResponseEnvelope {
+    /// Get the response body as a string slice.
+    ///
+    /// Returns an error if the body is not valid UTF-8.
+    pub fn body_str(&self) -> Result<&str, std::str::Utf8Error> {
+        std::str::from_utf8(&self.body)
+    }
+
+    /// Deserialize the GraphQL response, borrowing from the retained response body bytes.
+    ///
+    /// The response is processed according to its media type, as specified in the
+    /// GraphQL-over-HTTP specification.
+    pub fn decode<'a, ResponseData>(&'a self) -> Result<DecodedResponse<ResponseData>, RequestError>
+    where
+        ResponseData: serde::Deserialize<'a>,
+    {
+        // Process a GraphQL-over-HTTP response.
+        if is_graphql_response(&self.headers) {
+            decode_graphql_response(self.status, &self.headers, &self.body)
+        } else {
+            decode_legacy_graphql_response(self.status, &self.body)
+        }
+    }
+
+    /// Deserialize the GraphQL batch response, i.e., a JSON array of GraphQL responses,
+    /// borrowing from the retained response body bytes.
+    ///
+    /// The results are in the same order as the requests of the batch. Each GraphQL response is
+    /// deserialized independently, so a response that cannot be deserialized only fails its own
+    /// result. If the response body is not a JSON array, e.g., the server replied with a single
+    /// GraphQL response rejecting the batch, a [`RequestError::BatchNotSupported`] error is
+    /// returned.
+    #[allow(clippy::type_complexity)]
+    pub fn decode_batch<'a, ResponseData>(
+        &'a self,
+    ) -> Result<
+        Vec<Result<ResponseResult<PartialResponse<ResponseData>>, RequestError>>,
+        RequestError,
+    >
+    where
+        ResponseData: serde::Deserialize<'a>,
+    {
+        if is_graphql_response(&self.headers) {
+            check_charset(self.status, &self.headers)?;
+        }
+        check_response(self.status, &self.body)?;
+
+        // A server not supporting batching replies with a single GraphQL response, e.g., a
+        // request error, or any other non-array body.
+        let is_batch = self
+            .body
+            .iter()
+            .find(|b| !b.is_ascii_whitespace())
+            .is_some_and(|b| *b == b'[');
+        if !is_batch {
+            return Err(RequestError::BatchNotSupported(
+                self.status,
+                String::from_utf8_lossy(&self.body).to_string(),
+            ));
+        }
+
+        let responses: Vec<&serde_json::value::RawValue> = serde_json::from_slice(&self.body)
+            .map_err(|error| RequestError::ResponseDeserializationError {
+                error,
+                response: String::from_utf8_lossy(&self.body).to_string(),
+            })?;
+
+        Ok(responses
+            .into_iter()
+            .map(|response| {
+                serde_json::from_str::<ResponseBody<ResponseData>>(response.get())
+                    .map(process_response_body)
+                    .map_err(|error| RequestError::ResponseDeserializationError {
+                        error,
+                        response: response.get().to_string(),
+                    })
+            })
+            .collect())
+    }
+}
+
+/// Decode the GraphQL batch response, checking that it contains the `expected` number of
+/// results, i.e., one per request of the batch.
+fn decode_batch_results<ResponseData>(
+    envelope: &ResponseEnvelope,
+    expected: usize,
+) -> Result<Vec<Result<ResponseResult<ResponseData>, RequestError>>, RequestError>
+where
+    ResponseData: serde::de::DeserializeOwned,
+{
+    let results = envelope.decode_batch()?;
+    if results.len() != expected {
+        return Err(RequestError::BatchResponseMismatch {
+            expected,
+            received: results.len(),
+        });
     }
+
+    // Do not consider partial responses
+    Ok(results
+        .into_iter()
+        .map(|result| result.map(|result| result.and_then(PartialResponse::into_result)))
+        .collect())
+}
+
+/// Determine if the response is a GraphQL-over-HTTP response using the current
+/// `application/graphql-response+json` media type.
+///
+/// If no `Content-Type` header is present, the response SHOULD be interpreted as if the header
+/// field had the value `application/json` (legacy media type). Any other media type is also
+/// processed as a legacy response.
+fn is_graphql_response(headers: &HeaderMap) -> bool {
+    headers
+        .get(CONTENT_TYPE)
+        .and_then(|header| header.to_str().ok())
+        .map(ContentType::parse)
+        .is_some_and(|content_type| content_type.is(GRAPHQL_RESPONSE_MEDIA_TYPE))
+}
+
+/// Check that the response media type charset, if any, is UTF-8.
+///
+/// See the section [4.1 Media Types](https://graphql.github.io/graphql-over-http/draft/#sec-Media-Types)
+/// of the GraphQL-over-HTTP specification.
+fn check_charset(status: StatusCode, headers: &HeaderMap) -> Result<(), RequestError> {
+    // > Only UTF-8 encoding is supported in this specification. [...] a client MAY reject a
+    // > response that uses any other encoding.
+    let charset = headers
+        .get(CONTENT_TYPE)
+        .and_then(|header| header.to_str().ok())
+        .and_then(|header| ContentType::parse(header).charset());
+    if let Some(charset) = charset {
+        if !is_utf8_charset(charset) {
+            return Err(RequestError::ResponseRecvError(
+                status,
+                format!("Unsupported response charset: {charset}"),
+            ));
+        }
+    }
+
+    Ok(())
+}
+
+/// Check that the response status code is a `2xx`, `4xx` or `5xx` status code, and that the
+/// response body is not empty.
+fn check_response(status: StatusCode, body: &[u8]) -> Result<(), RequestError> {
+    if !status.is_success() && !status.is_client_error() && !status.is_server_error() {
+        let body = if body.is_empty() {
+            "Empty response body".to_string()
+        } else {
+            String::from_utf8_lossy(body).to_string()
+        };
+        return Err(RequestError::ResponseRecvError(status, body));
+    }
+
+    if body.is_empty() {
+        return Err(RequestError::ResponseRecvError(
+            status,
+            "Empty response body".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Deserialize the GraphQL response body, and process it.
+fn decode_response_body<'a, ResponseData>(
+    body: &'a [u8],
+) -> Result<DecodedResponse<ResponseData>, RequestError>
+where
+    ResponseData: serde::Deserialize<'a>,
+{
+    let mut response: ResponseBody<ResponseData> =
+        serde_json::from_slice(body).map_err(|error| {
+            RequestError::ResponseDeserializationError {
+                error,
+                response: String::from_utf8_lossy(body).to_string(),
+            }
+        })?;
+
+    let extensions = std::mem::take(&mut response.extensions);
+    Ok(DecodedResponse {
+        result: process_response_body(response),
+        extensions,
+    })
+}
+
+/// Decode the GraphQL-over-HTTP response when the media type, `application/graphql-response+json`,
+/// is used.
+///
+/// See the section [6.4.2 application/graphql-response+json](
+/// https://graphql.github.io/graphql-over-http/draft/#sec-application-graphql-response-json)
+/// of the GraphQL-over-HTTP specification for more information.
+fn decode_graphql_response<'a, ResponseData>(
+    status: StatusCode,
+    headers: &HeaderMap,
+    body: &'a [u8],
+) -> Result<DecodedResponse<ResponseData>, RequestError>
+where
+    ResponseData: serde::Deserialize<'a>,
+{
+    check_charset(status, headers)?;
+
+    // [6.4.2 application/graphql-response+json](https://graphql.github.io/graphql-over-http/draft/#sec-application-graphql-response-json)
+    //
+    // > If the GraphQL response contains the `data` entry and it is not `null`, then the server
+    // > MUST reply with a `2xx` status code.
+    //
+    // > If the GraphQL response does not contain the `data` entry, then the server MUST reply
+    // > with a `4xx` or `5xx` status code as appropriate.
+    check_response(status, body)?;
+
+    if status.is_success() {
+        return decode_response_body(body);
+    }
+
+    // A `4xx` or `5xx` status code carries a GraphQL request error, i.e., a well-formed
+    // GraphQL response without `data`. Any other body, e.g., an intermediary's error page, is
+    // not a GraphQL response and is reported as a failure to receive the response.
+    match serde_json::from_slice::<ResponseBody<serde::de::IgnoredAny>>(body) {
+        Ok(ResponseBody {
+            data: None,
+            errors,
+            extensions,
+        }) if !errors.is_empty() => Ok(DecodedResponse {
+            result: Err(ResponseError::Failure { errors }),
+            extensions,
+        }),
+        _ => Err(RequestError::ResponseRecvError(
+            status,
+            String::from_utf8_lossy(body).to_string(),
+        )),
+    }
+}
+
+/// Decode the GraphQL-over-HTTP response when the legacy media type, `application/json`, is used.
+///
+/// See the section [6.4.1 application/json](https://graphql.github.io/graphql-over-http/draft/#sec-application-json)
+/// of the GraphQL-over-HTTP specification for more information.
+fn decode_legacy_graphql_response<'a, ResponseData>(
+    status: StatusCode,
+    body: &'a [u8],
+) -> Result<DecodedResponse<ResponseData>, RequestError>
+where
+    ResponseData: serde::Deserialize<'a>,
+{
+    // [6.4.1 application/json](https://graphql.github.io/graphql-over-http/draft/#sec-application-json)
+    //
+    // > The server SHOULD use the 200 status code for every response to a well-formed
+    // > GraphQL-over-HTTP request, independent of any GraphQL request error or GraphQL field error
+    // > raised.
+    //
+    // > For compatibility with legacy servers, this specification allows the use of `4xx` or `5xx`
+    // > status codes for a failed well-formed GraphQL-over-HTTP request where the response uses
+    // > the `application/json` media type, but it is **strongly discouraged**.
+    check_response(status, body)?;
+
+    decode_response_body(body)
 }
 
 #[cfg(feature = "reqwest")]
 mod reqwest_ext {
     use async_trait::async_trait;
-    use reqwest::header::{ACCEPT, CONTENT_TYPE};
+    use bytes::Bytes;
+    use reqwest::{
+        Method,
+        header::{CONTENT_TYPE, HeaderValue},
+    };
 
-    use super::{RequestError, ResponseResult, process_response_body};
+    use super::{
+        DecodedResponse, GetRequestOptions, GraphQlClient, GraphQlTransport, IncrementalStream,
+        MAX_INCREMENTAL_PART_SIZE, PartialResponse, RequestError, ResponseEnvelope, ResponseError,
+        ResponseResult, accept_header_value, graphql_get_request, graphql_post_headers,
+        graphql_post_request, multipart::MultipartParser,
+    };
     use crate::http::{
-        request::{GRAPHQL_REQUEST_MEDIA_TYPE, IntoRequestParameters},
+        content_type::ContentType,
+        incremental::{
+            DEFAULT_MULTIPART_BOUNDARY, IncrementalPayload, InitialPayload,
+            MULTIPART_MIXED_MEDIA_TYPE, SubsequentPayload,
+        },
+        request::IntoRequestParameters,
         response::{GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE, GRAPHQL_RESPONSE_MEDIA_TYPE},
     };
 
     /// An extension trait for reqwest::RequestBuilder.
+    ///
+    /// The GraphQL requests are sent by a [`GraphQlClient`] using the request builder client,
+    /// URL, headers and options, e.g., the timeout, as the transport.
     #[cfg(feature = "reqwest")]
     #[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
     #[async_trait]
@@ -102,6 +654,49 @@ mod reqwest_ext {
         where
             Self: Sized;
 
+        /// Sets the `Content-Type` and `Accept` headers to the GraphQL-over-HTTP media types and
+        /// serializes the GraphQL requests as a batch, i.e., a JSON array of request parameters.
+        ///
+        /// Batched requests are not part of the GraphQL-over-HTTP specification, but are
+        /// supported by some servers, e.g., some _The Graph_ gateways and indexers.
+        ///
+        /// If any of the GraphQL requests cannot be serialized, an error is returned.
+        fn graphql_batch<Req>(
+            self,
+            reqs: impl IntoIterator<Item = Req>,
+        ) -> Result<Self, serde_json::Error>
+        where
+            Self: Sized,
+            Req: IntoRequestParameters;
+
+        /// Sets the `Content-Type` and `Accept` headers, accepting incremental delivery
+        /// `multipart/mixed` responses along with the GraphQL-over-HTTP media types, and serializes
+        /// the GraphQL request.
+        ///
+        /// If the GraphQL request cannot be serialized, an error is returned.
+        fn graphql_incremental(
+            self,
+            req: impl IntoRequestParameters,
+        ) -> Result<Self, serde_json::Error>
+        where
+            Self: Sized;
+
+        /// Sets the method to `GET`, the `Accept` header to the GraphQL-over-HTTP media types, and
+        /// serializes the GraphQL request into the URL query string.
+        ///
+        /// As specified in the GraphQL-over-HTTP specification, only query operations can be sent
+        /// using the `GET` method. With the `graphql-parser` feature enabled, other operations,
+        /// e.g., mutations, are rejected. If the resulting URL is longer than
+        /// [`GetRequestOptions::max_url_length`], the request is sent using the `POST` method
+        /// instead, see [`ReqwestExt::graphql`].
+        fn graphql_get(
+            self,
+            req: impl IntoRequestParameters,
+            options: GetRequestOptions,
+        ) -> Result<Self, RequestError>
+        where
+            Self: Sized;
+
         /// Runs a GraphQL query with the parameters in RequestBuilder, deserializes
         /// the body and returns the result.
         async fn send_graphql<ResponseData>(
@@ -110,6 +705,101 @@ mod reqwest_ext {
         ) -> Result<ResponseResult<ResponseData>, RequestError>
         where
             ResponseData: serde::de::DeserializeOwned;
+
+        /// Runs a GraphQL query with the parameters in RequestBuilder, deserializes
+        /// the body and returns the result, keeping the data of partial responses.
+        ///
+        /// Unlike [`ReqwestExt::send_graphql`], a response carrying data along with field errors is
+        /// returned as a [`PartialResponse`] instead of a [`ResponseError::Failure`].
+        async fn send_graphql_partial<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseResult<PartialResponse<ResponseData>>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned;
+
+        /// Runs a GraphQL query with the parameters in RequestBuilder using the `GET` method,
+        /// deserializes the body and returns the result.
+        ///
+        /// See [`ReqwestExt::graphql_get`] for more information.
+        async fn send_graphql_get<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+            options: GetRequestOptions,
+        ) -> Result<ResponseResult<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned;
+
+        /// Runs a batch of GraphQL queries with the parameters in RequestBuilder, deserializes the
+        /// body and returns the results, in the same order as the requests.
+        ///
+        /// Each result is the one [`ReqwestExt::send_graphql`] would return for the request, e.g.,
+        /// a GraphQL response that cannot be deserialized only fails its own result.
+        ///
+        /// If the server does not support batching, i.e., it does not reply with a JSON array of
+        /// GraphQL responses, a [`RequestError::BatchNotSupported`] error is returned. An empty
+        /// batch is not sent, and returns no results.
+        ///
+        /// See [`ReqwestExt::graphql_batch`] for more information.
+        async fn send_graphql_batch<ResponseData, Req>(
+            self,
+            reqs: Vec<Req>,
+        ) -> Result<Vec<Result<ResponseResult<ResponseData>, RequestError>>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned,
+            Req: IntoRequestParameters + Send;
+
+        /// Runs a GraphQL query with the parameters in RequestBuilder using automatic persisted
+        /// queries, deserializes the body and returns the result.
+        ///
+        /// The SHA-256 hash of the GraphQL document is sent first, without the document itself. If
+        /// the server does not know the hash, the request is retried with both the document and
+        /// its hash, so the server can store it. If the server does not support persisted queries,
+        /// the request is retried without the hash.
+        ///
+        /// The request builder does not keep track of the server support, so every request to a
+        /// server without persisted queries support costs two round trips. Prefer
+        /// [`GraphQlClient::send_graphql_persisted`], which does, or [`ReqwestExt::send_graphql`]
+        /// for such servers.
+        ///
+        /// See the [`persisted_query`](crate::persisted_query) module for more information.
+        async fn send_graphql_persisted<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseResult<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned;
+
+        /// Runs a GraphQL query with the parameters in RequestBuilder, accepting incremental
+        /// delivery, i.e., `@defer` and `@stream`, responses, and returns the stream of
+        /// deserialized response payloads.
+        ///
+        /// The `multipart/mixed` response body is parsed as it is received. The first payload is
+        /// the [`IncrementalPayload::Initial`] payload, followed by the
+        /// [`IncrementalPayload::Subsequent`] payloads, until a payload without more payloads to
+        /// follow. If the server replies with a regular GraphQL response, e.g., the operation
+        /// does not use `@defer` or `@stream`, it is returned as a single initial payload.
+        ///
+        /// A response part larger than [`MAX_INCREMENTAL_PART_SIZE`] ends the stream with a
+        /// [`RequestError::ResponseRecvError`] error.
+        ///
+        /// See [`merge_payloads`](crate::http::incremental::merge_payloads) to merge the payloads
+        /// into the final response.
+        async fn send_graphql_incremental<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<IncrementalStream<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned + Send + 'static;
+
+        /// Runs a GraphQL query with the parameters in RequestBuilder and returns the
+        /// [`ResponseEnvelope`], i.e., the response status, headers and raw body bytes.
+        ///
+        /// The response body is not deserialized, see [`ResponseEnvelope::decode`].
+        async fn send_graphql_envelope(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseEnvelope, RequestError>;
     }
 
     #[cfg(feature = "reqwest")]
@@ -123,24 +813,63 @@ mod reqwest_ext {
             let gql_request = req.into_request_parameters();
             let gql_request_body = serde_json::to_vec(&gql_request)?;
 
-            let builder = self
-                // Set `Content-Type` header to `application/json` as specified in the section
-                // [5.4 POST](https://graphql.github.io/graphql-over-http/draft/#sec-POST) of the
-                // GraphQL-over-HTTP specification.
-                .header(CONTENT_TYPE, GRAPHQL_REQUEST_MEDIA_TYPE)
-                // Set `Accept` header to `application/json` and `application/graphql-response+json` to
-                // support both the legacy and the current GraphQL-over-HTTP media types. As specified in
-                // the section [5.2.1 Legacy Watershed](https://graphql.github.io/graphql-over-http/draft/#sec-Legacy-Watershed)
-                // of the GraphQL-over-HTTP specification.
-                .header(
-                    ACCEPT,
-                    format!(
-                        "{GRAPHQL_RESPONSE_MEDIA_TYPE}; charset=utf-8, {GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE}; charset=utf-8"
-                    ),
-                )
-                .body(gql_request_body);
+            Ok(self
+                .headers(graphql_post_headers(accept_header_value()))
+                .body(gql_request_body))
+        }
+
+        fn graphql_batch<Req>(
+            self,
+            reqs: impl IntoIterator<Item = Req>,
+        ) -> Result<Self, serde_json::Error>
+        where
+            Self: Sized,
+            Req: IntoRequestParameters,
+        {
+            let gql_requests = reqs
+                .into_iter()
+                .map(IntoRequestParameters::into_request_parameters)
+                .collect::<Vec<_>>();
+            let gql_request_body = serde_json::to_vec(&gql_requests)?;
 
-            Ok(builder)
+            Ok(self
+                .headers(graphql_post_headers(accept_header_value()))
+                .body(gql_request_body))
+        }
+
+        fn graphql_incremental(
+            self,
+            req: impl IntoRequestParameters,
+        ) -> Result<Self, serde_json::Error>
+        where
+            Self: Sized,
+        {
+            let gql_request = req.into_request_parameters();
+            let gql_request_body = serde_json::to_vec(&gql_request)?;
+
+            Ok(self
+                .headers(graphql_post_headers(incremental_accept_header_value()))
+                .body(gql_request_body))
+        }
+
+        fn graphql_get(
+            self,
+            req: impl IntoRequestParameters,
+            options: GetRequestOptions,
+        ) -> Result<Self, RequestError>
+        where
+            Self: Sized,
+        {
+            let gql_request = req.into_request_parameters();
+
+            let (client, request) = self.build_split();
+            let mut request = request?;
+
+            let graphql_request =
+                graphql_get_request(&request_url(&request)?, &gql_request, options)?;
+            set_graphql_request(&mut request, graphql_request)?;
+
+            Ok(reqwest::RequestBuilder::from_parts(client, request))
         }
 
         async fn send_graphql<ResponseData>(
@@ -150,106 +879,405 @@ mod reqwest_ext {
         where
             ResponseData: serde::de::DeserializeOwned,
         {
-            let builder = self
-                .graphql(req)
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql(req).await
+        }
+
+        async fn send_graphql_partial<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseResult<PartialResponse<ResponseData>>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned,
+        {
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql_partial(req).await
+        }
+
+        async fn send_graphql_get<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+            options: GetRequestOptions,
+        ) -> Result<ResponseResult<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned,
+        {
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql_get(req, options).await
+        }
+
+        async fn send_graphql_batch<ResponseData, Req>(
+            self,
+            reqs: Vec<Req>,
+        ) -> Result<Vec<Result<ResponseResult<ResponseData>, RequestError>>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned,
+            Req: IntoRequestParameters + Send,
+        {
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql_batch(reqs).await
+        }
+
+        async fn send_graphql_persisted<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseResult<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned,
+        {
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql_persisted(req).await
+        }
+
+        async fn send_graphql_incremental<ResponseData>(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<IncrementalStream<ResponseData>, RequestError>
+        where
+            ResponseData: serde::de::DeserializeOwned + Send + 'static,
+        {
+            let gql_request = req.into_request_parameters();
+            let gql_request_body = serde_json::to_vec(&gql_request)
                 .map_err(RequestError::RequestSerializationError)?;
 
-            match builder.send().await {
-                Ok(response) => {
-                    // Process a GraphQL-over-HTTP response.
-                    if !is_legacy_response(&response) {
-                        process_graphql_response(response).await
-                    } else {
-                        process_legacy_graphql_response(response).await
-                    }
-                }
-                Err(e) => Err(RequestError::RequestSendError(e)),
+            let (transport, url) = RequestBuilderTransport::new(self)?;
+            let response = transport
+                .execute(graphql_post_request(
+                    &url,
+                    incremental_accept_header_value(),
+                    gql_request_body,
+                ))
+                .await?;
+
+            let boundary = response
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|header| header.to_str().ok())
+                .map(ContentType::parse)
+                .filter(|content_type| content_type.is(MULTIPART_MIXED_MEDIA_TYPE))
+                .map(|content_type| {
+                    content_type
+                        .param("boundary")
+                        .unwrap_or(DEFAULT_MULTIPART_BOUNDARY)
+                        .to_string()
+                });
+
+            // Not an incremental delivery response, e.g., the operation does not use `@defer` or
+            // `@stream`, return it as a single initial payload
+            let Some(boundary) = boundary else {
+                let envelope = ResponseEnvelope::from_response(response).await?;
+                let payload = initial_payload(envelope.decode()?);
+                return Ok(Box::pin(futures_util::stream::iter([Ok(
+                    IncrementalPayload::Initial(payload),
+                )])));
+            };
+
+            let status = response.status();
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(RequestError::ResponseRecvError(status, body));
             }
+
+            Ok(Box::pin(incremental_stream(response, &boundary)))
+        }
+
+        async fn send_graphql_envelope(
+            self,
+            req: impl IntoRequestParameters + Send,
+        ) -> Result<ResponseEnvelope, RequestError> {
+            let client = RequestBuilderTransport::client(self)?;
+            client.send_graphql_envelope(req).await
+        }
+    }
+
+    /// A [`GraphQlTransport`] sending the GraphQL requests using the client, headers and options,
+    /// e.g., the timeout, of a [`reqwest::RequestBuilder`].
+    ///
+    /// The request builder body, if any, is discarded, as it is replaced by the GraphQL request.
+    struct RequestBuilderTransport {
+        client: reqwest::Client,
+        request: reqwest::Request,
+    }
+
+    impl RequestBuilderTransport {
+        /// Create a new transport from the request builder, and return it along with the request
+        /// URL.
+        fn new(builder: reqwest::RequestBuilder) -> Result<(Self, http::Uri), RequestError> {
+            let (client, request) = builder.build_split();
+            let mut request = request?;
+            *request.body_mut() = None;
+
+            let url = request_url(&request)?;
+            Ok((Self { client, request }, url))
+        }
+
+        /// Create a [`GraphQlClient`] sending the GraphQL requests to the request builder URL.
+        fn client(builder: reqwest::RequestBuilder) -> Result<GraphQlClient<Self>, RequestError> {
+            let (transport, url) = Self::new(builder)?;
+            Ok(GraphQlClient::new(transport, url))
+        }
+
+        /// Send the GraphQL request, and return the response without receiving its body.
+        async fn execute(
+            &self,
+            graphql_request: http::Request<Bytes>,
+        ) -> Result<reqwest::Response, RequestError> {
+            let mut request = self
+                .request
+                .try_clone()
+                .expect("request without body to be cloneable");
+            set_graphql_request(&mut request, graphql_request)?;
+
+            Ok(self.client.execute(request).await?)
+        }
+    }
+
+    #[async_trait]
+    impl GraphQlTransport for RequestBuilderTransport {
+        async fn send(
+            &self,
+            request: http::Request<Bytes>,
+        ) -> Result<ResponseEnvelope, RequestError> {
+            let response = self.execute(request).await?;
+            ResponseEnvelope::from_response(response).await
+        }
+    }
+
+    /// Get the request URL as an `http::Uri`.
+    fn request_url(request: &reqwest::Request) -> Result<http::Uri, RequestError> {
+        // URL fragments are not sent to the server, and are not supported by `http::Uri`
+        let mut url = request.url().clone();
+        url.set_fragment(None);
+
+        url.as_str()
+            .parse()
+            .map_err(|err: http::uri::InvalidUri| RequestError::TransportError(err.into()))
+    }
+
+    /// Set the GraphQL request method, URL, headers and body on the request.
+    ///
+    /// The GraphQL request headers replace the request ones, and the `Content-Type` header is
+    /// removed if the GraphQL request has no body, i.e., a `GET` request.
+    fn set_graphql_request(
+        request: &mut reqwest::Request,
+        graphql_request: http::Request<Bytes>,
+    ) -> Result<(), RequestError> {
+        let mut graphql_request = reqwest::Request::try_from(graphql_request)?;
+        let has_body = graphql_request.method() != Method::GET;
+
+        *request.method_mut() = graphql_request.method().clone();
+        *request.url_mut() = graphql_request.url().clone();
+
+        let headers = request.headers_mut();
+        headers.remove(CONTENT_TYPE);
+        for (name, value) in graphql_request.headers() {
+            headers.insert(name, value.clone());
         }
+
+        *request.body_mut() = graphql_request.body_mut().take().filter(|_| has_body);
+        Ok(())
     }
 
-    /// Determine if the response is a GraphQL-over-HTTP response using the legacy media type.
-    fn is_legacy_response(response: &reqwest::Response) -> bool {
-        let content_type = response.headers().get(CONTENT_TYPE);
-        match content_type {
-            // If the `Content-Type` header is present, check if it is the legacy response media
-            // type or the current GraphQL-over-HTTP response media type.
-            Some(header) => header
-                .as_bytes()
-                .eq_ignore_ascii_case(GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE.as_bytes()),
-            // If no `Content-Type` header is present, the response SHOULD be interpreted as if the
-            // header field had the value `application/json` (legacy media type).
-            None => true,
+    /// The `Accept` header value of incremental delivery requests, preferring the `multipart/mixed`
+    /// media type, and supporting both the legacy and the current GraphQL-over-HTTP media types.
+    fn incremental_accept_header_value() -> HeaderValue {
+        let value = format!(
+            "{MULTIPART_MIXED_MEDIA_TYPE}; deferSpec=20220824, {GRAPHQL_RESPONSE_MEDIA_TYPE}; charset=utf-8, {GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE}; charset=utf-8"
+        );
+        HeaderValue::from_str(&value).expect("header to be valid ascii")
+    }
+
+    /// Convert a regular GraphQL response into an incremental delivery initial payload.
+    fn initial_payload<ResponseData>(
+        response: DecodedResponse<ResponseData>,
+    ) -> InitialPayload<ResponseData> {
+        let (data, errors) = match response.result {
+            Ok(PartialResponse { data, errors }) => (Some(data), errors),
+            Err(ResponseError::Failure { errors }) => (None, errors),
+            Err(ResponseError::Empty) => (None, vec![]),
+        };
+        InitialPayload {
+            data,
+            errors,
+            extensions: response.extensions,
+            has_next: false,
         }
     }
 
-    /// Process the GraphQL-over-HTTP response when the media type, `application/graphql-response+json`,
-    /// is used.
+    /// Parse the incremental delivery `multipart/mixed` response body as it is received, and
+    /// deserialize its payloads.
     ///
-    /// See the section [6.4.2 application/graphql-response+json](
-    /// https://graphql.github.io/graphql-over-http/draft/#sec-application-graphql-response-json)
-    /// of the GraphQL-over-HTTP specification for more information.
-    async fn process_graphql_response<ResponseData>(
-        resp: reqwest::Response,
-    ) -> Result<ResponseResult<ResponseData>, RequestError>
+    /// The stream ends after the first payload without more payloads to follow, or after the first
+    /// error.
+    fn incremental_stream<ResponseData>(
+        response: reqwest::Response,
+        boundary: &str,
+    ) -> impl futures_util::Stream<Item = Result<IncrementalPayload<ResponseData>, RequestError>>
+    + Send
+    + use<ResponseData>
     where
-        ResponseData: serde::de::DeserializeOwned,
+        ResponseData: serde::de::DeserializeOwned + Send,
     {
-        // TODO: Add support for the GraphQL-over-HTTP response media type (non-legacy)
-        //  Fall back to legacy media type for now.
-        process_legacy_graphql_response(resp).await
+        /// The incremental delivery response body parsing state.
+        struct State {
+            response: reqwest::Response,
+            parser: MultipartParser,
+            initial: bool,
+            done: bool,
+        }
+
+        let state = State {
+            response,
+            parser: MultipartParser::new(boundary, MAX_INCREMENTAL_PART_SIZE),
+            initial: true,
+            done: false,
+        };
+
+        futures_util::stream::unfold(state, |mut state| async move {
+            let status = state.response.status();
+            while !state.done {
+                let err = match state.parser.next_part() {
+                    Ok(Some(part)) => {
+                        // Skip empty parts, e.g., heartbeats
+                        if matches!(part.trim_ascii(), b"" | b"{}") {
+                            continue;
+                        }
+
+                        let payload = decode_incremental_payload(&part, state.initial);
+                        state.initial = false;
+                        state.done = payload.as_ref().map_or(true, |payload| !payload.has_next());
+                        return Some((payload, state));
+                    }
+                    Ok(None) if state.parser.is_finished() => break,
+                    // Receive the next response body chunk
+                    Ok(None) => match state.response.chunk().await {
+                        Ok(Some(chunk)) => {
+                            state.parser.push(&chunk);
+                            continue;
+                        }
+                        Ok(None) => "Unexpected end of multipart response body".to_string(),
+                        Err(err) => format!("Error reading response body: {err}"),
+                    },
+                    Err(err) => err.to_string(),
+                };
+                state.done = true;
+                return Some((Err(RequestError::ResponseRecvError(status, err)), state));
+            }
+            None
+        })
     }
 
-    /// Process the GraphQL-over-HTTP response when the legacy media type, `application/json`, is used.
-    ///
-    /// See the section [6.4.1 application/json](https://graphql.github.io/graphql-over-http/draft/#sec-application-json)
-    /// of the GraphQL-over-HTTP specification for more information.
-    async fn process_legacy_graphql_response<ResponseData>(
-        resp: reqwest::Response,
-    ) -> Result<ResponseResult<ResponseData>, RequestError>
+    /// Deserialize an incremental delivery payload, the initial payload or a subsequent one.
+    fn decode_incremental_payload<ResponseData>(
+        body: &[u8],
+        initial: bool,
+    ) -> Result<IncrementalPayload<ResponseData>, RequestError>
     where
         ResponseData: serde::de::DeserializeOwned,
     {
-        let status = resp.status();
-
-        // [6.4.1 application/json](https://graphql.github.io/graphql-over-http/draft/#sec-application-json)
-        //
-        // > The server SHOULD use the 200 status code for every response to a well-formed
-        // > GraphQL-over-HTTP request, independent of any GraphQL request error or GraphQL field error
-        // > raised.
-        //
-        // > For compatibility with legacy servers, this specification allows the use of `4xx` or `5xx`
-        // > status codes for a failed well-formed GraphQL-over-HTTP request where the response uses
-        // > the `application/json` media type, but it is **strongly discouraged**.
-        if !status.is_success() && !status.is_client_error() && !status.is_server_error() {
-            return Err(RequestError::ResponseRecvError(
-                status,
-                resp.text()
-                    .await
-                    .unwrap_or_else(|_| "Empty response body".to_string()),
-            ));
-        }
+        let payload = if initial {
+            serde_json::from_slice::<InitialPayload<ResponseData>>(body)
+                .map(IncrementalPayload::Initial)
+        } else {
+            serde_json::from_slice::<SubsequentPayload>(body).map(IncrementalPayload::Subsequent)
+        };
+        payload.map_err(|error| RequestError::ResponseDeserializationError {
+            error,
+            response: String::from_utf8_lossy(body).to_string(),
+        })
+    }
 
-        // Receive the response body.
-        let response = resp.bytes().await.map_err(|err| {
-            RequestError::ResponseRecvError(status, format!("Error reading response body: {err}"))
-        })?;
+    impl ResponseEnvelope {
+        /// Receive the response status, headers and body of a GraphQL-over-HTTP response.
+        pub async fn from_response(response: reqwest::Response) -> Result<Self, RequestError> {
+            let status = response.status();
+            let headers = response.headers().clone();
 
-        if response.is_empty() {
-            return Err(RequestError::ResponseRecvError(
+            // Receive the response body.
+            let body = response.bytes().await.map_err(|err| {
+                RequestError::ResponseRecvError(
+                    status,
+                    format!("Error reading response body: {err}"),
+                )
+            })?;
+
+            Ok(Self {
                 status,
-                "Empty response body".to_string(),
-            ));
+                headers,
+                body,
+            })
         }
+    }
+}
 
-        // Deserialize the response body.
-        let response = serde_json::from_slice(&response).map_err(|error| {
-            RequestError::ResponseDeserializationError {
-                error,
-                response: String::from_utf8_lossy(&response).to_string(),
-            }
-        })?;
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::{ResponseError, process_response_body};
+    use crate::http::response::ResponseBody;
+
+    /// Deserialize the given string as a GraphQL response body.
+    fn response_body(body: &str) -> ResponseBody<serde_json::Value> {
+        serde_json::from_str(body).expect("valid response body")
+    }
+
+    #[test]
+    fn process_complete_response() {
+        //* Given
+        let body = response_body(r#"{"data":{"field":"value"}}"#);
+
+        //* When
+        let response = process_response_body(body);
+
+        //* Then
+        assert_matches!(response, Ok(response) => {
+            assert!(response.is_complete());
+            assert_matches!(response.into_result(), Ok(data) => {
+                assert_eq!(data["field"], "value");
+            });
+        });
+    }
+
+    #[test]
+    fn process_partial_response_keeps_data() {
+        //* Given
+        let body = response_body(
+            r#"{"data":{"field":null,"other":"value"},"errors":[{"message":"Field error","path":["field"]}]}"#,
+        );
+
+        //* When
+        let response = process_response_body(body);
+
+        //* Then
+        assert_matches!(response, Ok(response) => {
+            assert!(!response.is_complete());
+            assert_eq!(response.data["other"], "value");
+            assert_eq!(response.errors[0].message, "Field error");
+
+            // Strict handling discards the data
+            assert_matches!(response.into_result(), Err(ResponseError::Failure { errors }) => {
+                assert_eq!(errors.len(), 1);
+            });
+        });
+    }
+
+    #[test]
+    fn process_response_without_data() {
+        //* Given
+        let failure = response_body(r#"{"errors":[{"message":"Request error"}]}"#);
+        let null_data = response_body(r#"{"data":null,"errors":[{"message":"Field error"}]}"#);
+        let empty = response_body(r#"{}"#);
 
-        Ok(process_response_body(response))
+        //* Then
+        assert_matches!(
+            process_response_body(failure),
+            Err(ResponseError::Failure { .. })
+        );
+        assert_matches!(
+            process_response_body(null_data),
+            Err(ResponseError::Failure { .. })
+        );
+        assert_matches!(process_response_body(empty), Err(ResponseError::Empty));
     }
 }
@@ -0,0 +1,285 @@
+//! Incremental `multipart/mixed` response body parsing.
+//!
+//! See the section [5.1 Multipart Media Type](https://www.rfc-editor.org/rfc/rfc2046#section-5.1)
+//! of RFC 2046 for more information.
+
+/// The error returned when a part exceeds the parser maximum part size.
+#[derive(Debug, thiserror::Error)]
+#[error("Multipart response part exceeds the maximum size of {max} bytes")]
+pub(super) struct PartTooLarge {
+    max: usize,
+}
+
+/// An incremental `multipart/mixed` body parser.
+///
+/// The body chunks are pushed as they are received, and the complete parts' bodies are pulled
+/// one at a time. The parts' headers are discarded.
+///
+/// The received bytes are scanned for boundary delimiters only once, and the buffered bytes are
+/// bounded by the maximum part size.
+#[derive(Debug)]
+pub(super) struct MultipartParser {
+    /// The boundary delimiter, i.e., the boundary preceded by a line break and two hyphens.
+    delimiter: Vec<u8>,
+    /// The received bytes not processed yet.
+    buffer: Vec<u8>,
+    /// The number of buffered bytes already scanned for a boundary delimiter.
+    scanned: usize,
+    /// The maximum size of a part, including its headers.
+    max_part_size: usize,
+    /// Whether the first boundary delimiter was found, i.e., the preamble was skipped.
+    started: bool,
+    /// Whether the close boundary delimiter was found.
+    finished: bool,
+}
+
+impl MultipartParser {
+    /// Create a new parser for a body with the given boundary, rejecting parts larger than the
+    /// given size.
+    pub(super) fn new(boundary: &str, max_part_size: usize) -> Self {
+        Self {
+            delimiter: format!("\r\n--{boundary}").into_bytes(),
+            // The first boundary delimiter is not preceded by a line break if there is no
+            // preamble
+            buffer: b"\r\n".to_vec(),
+            scanned: 0,
+            max_part_size,
+            started: false,
+            finished: false,
+        }
+    }
+
+    /// Whether the close boundary delimiter was found, i.e., no more parts follow.
+    pub(super) fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    /// Push a received body chunk.
+    pub(super) fn push(&mut self, chunk: &[u8]) {
+        if !self.finished {
+            self.buffer.extend_from_slice(chunk);
+        }
+    }
+
+    /// Pull the body of the next complete part, if any.
+    ///
+    /// Returns an error if the part being received exceeds the maximum part size.
+    pub(super) fn next_part(&mut self) -> Result<Option<Vec<u8>>, PartTooLarge> {
+        if self.finished {
+            return Ok(None);
+        }
+
+        // Skip the preamble
+        if !self.started {
+            let Some(pos) = self.find_delimiter() else {
+                // Keep the bytes that could be the start of a delimiter split across chunks
+                let keep = self
+                    .delimiter
+                    .len()
+                    .saturating_sub(1)
+                    .min(self.buffer.len());
+                self.consume(self.buffer.len() - keep);
+                return Ok(None);
+            };
+            self.consume(pos + self.delimiter.len());
+            self.started = true;
+        }
+
+        // The buffer starts right after a boundary delimiter, followed by two hyphens if it is the
+        // close delimiter
+        if self.buffer.len() < 2 {
+            return Ok(None);
+        }
+        if self.buffer.starts_with(b"--") {
+            self.finished = true;
+            self.buffer.clear();
+            self.scanned = 0;
+            return Ok(None);
+        }
+
+        let Some(pos) = self.find_delimiter() else {
+            // The trailing bytes could be the start of a delimiter split across chunks
+            let part_size = self.buffer.len().saturating_sub(self.delimiter.len() - 1);
+            if part_size > self.max_part_size {
+                return Err(PartTooLarge {
+                    max: self.max_part_size,
+                });
+            }
+            return Ok(None);
+        };
+        if pos > self.max_part_size {
+            return Err(PartTooLarge {
+                max: self.max_part_size,
+            });
+        }
+
+        let part = part_body(&self.buffer[..pos]).to_vec();
+        self.consume(pos + self.delimiter.len());
+
+        Ok(Some(part))
+    }
+
+    /// Find the position of the next boundary delimiter in the buffer.
+    ///
+    /// The scan resumes where the previous one stopped, overlapping it by the delimiter length
+    /// minus one byte, so a delimiter split across chunks is found.
+    fn find_delimiter(&mut self) -> Option<usize> {
+        let start = self
+            .scanned
+            .saturating_sub(self.delimiter.len() - 1)
+            .min(self.buffer.len());
+        match self.buffer[start..]
+            .windows(self.delimiter.len())
+            .position(|window| window == self.delimiter)
+        {
+            Some(pos) => {
+                self.scanned = start + pos;
+                Some(start + pos)
+            }
+            None => {
+                self.scanned = self.buffer.len();
+                None
+            }
+        }
+    }
+
+    /// Remove the first `len` bytes of the buffer, keeping track of the scanned bytes.
+    fn consume(&mut self, len: usize) {
+        self.buffer.drain(..len);
+        self.scanned = self.scanned.saturating_sub(len);
+    }
+}
+
+/// Get the body of the given part, i.e., skip the rest of the boundary delimiter line and the part
+/// headers.
+fn part_body(part: &[u8]) -> &[u8] {
+    // Skip the boundary delimiter transport padding, and its line break
+    let Some(line_end) = part.windows(2).position(|window| window == b"\r\n") else {
+        return &[];
+    };
+    let part = &part[line_end + 2..];
+
+    // A part without headers starts with a blank line
+    if let Some(body) = part.strip_prefix(b"\r\n") {
+        return body;
+    }
+    match part.windows(4).position(|window| window == b"\r\n\r\n") {
+        Some(headers_end) => &part[headers_end + 4..],
+        None => &[],
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+
+    use super::MultipartParser;
+
+    /// A canned incremental delivery response body.
+    const BODY: &str = "preamble\r\n\
+        --graphql\r\n\
+        content-type: application/json; charset=utf-8\r\n\
+        \r\n\
+        {\"data\":{\"a\":1},\"hasNext\":true}\r\n\
+        --graphql\r\n\
+        \r\n\
+        {\"hasNext\":false}\r\n\
+        --graphql--\r\n\
+        epilogue";
+
+    /// Push the body in chunks of the given size, and collect the parts' bodies.
+    fn parse_in_chunks(body: &[u8], chunk_size: usize) -> (Vec<String>, bool) {
+        let mut parser = MultipartParser::new("graphql", 1024);
+        let mut parts = Vec::new();
+        for chunk in body.chunks(chunk_size) {
+            parser.push(chunk);
+            while let Some(part) = parser
+                .next_part()
+                .expect("part to be within the size limit")
+            {
+                parts.push(String::from_utf8(part).unwrap());
+            }
+        }
+        (parts, parser.is_finished())
+    }
+
+    #[test]
+    fn parse_multipart_body() {
+        //* When
+        let (parts, finished) = parse_in_chunks(BODY.as_bytes(), BODY.len());
+
+        //* Then
+        assert_eq!(
+            parts,
+            vec![
+                r#"{"data":{"a":1},"hasNext":true}"#.to_string(),
+                r#"{"hasNext":false}"#.to_string(),
+            ]
+        );
+        assert!(finished);
+    }
+
+    #[test]
+    fn parse_multipart_body_split_across_chunks() {
+        //* Given
+        let (expected, _) = parse_in_chunks(BODY.as_bytes(), BODY.len());
+
+        //* Then
+        for chunk_size in 1..BODY.len() {
+            let (parts, finished) = parse_in_chunks(BODY.as_bytes(), chunk_size);
+            assert_eq!(parts, expected, "chunk size: {chunk_size}");
+            assert!(finished, "chunk size: {chunk_size}");
+        }
+    }
+
+    #[test]
+    fn parse_multipart_body_without_preamble() {
+        //* Given
+        let body = "--graphql\r\n\r\n{}\r\n--graphql--";
+
+        //* When
+        let (parts, finished) = parse_in_chunks(body.as_bytes(), body.len());
+
+        //* Then
+        assert_eq!(parts, vec!["{}".to_string()]);
+        assert!(finished);
+    }
+
+    #[test]
+    fn wait_for_incomplete_part() {
+        //* Given
+        let mut parser = MultipartParser::new("-", 1024);
+
+        //* When
+        parser.push(b"\r\n---\r\ncontent-type: application/json\r\n\r\n{\"hasNext\":");
+
+        //* Then
+        assert_matches!(parser.next_part(), Ok(None));
+        assert!(!parser.is_finished());
+    }
+
+    #[test]
+    fn fail_on_part_exceeding_max_size() {
+        //* Given
+        let mut parser = MultipartParser::new("graphql", 16);
+        parser.push(b"--graphql\r\n\r\n{}\r\n--graphql\r\n\r\n");
+
+        //* When
+        let first = parser.next_part();
+
+        // Push the second part body, without its closing delimiter
+        parser.push(&[b' '; 32]);
+        let second = parser.next_part();
+
+        //* Then
+        assert_matches!(first, Ok(Some(part)) => {
+            assert_eq!(part, b"{}");
+        });
+        assert_matches!(second, Err(err) => {
+            assert_eq!(
+                err.to_string(),
+                "Multipart response part exceeds the maximum size of 16 bytes"
+            );
+        });
+    }
+}
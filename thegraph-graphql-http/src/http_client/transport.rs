@@ -0,0 +1,372 @@
+//! Transport-independent GraphQL-over-HTTP client.
+//!
+//! The [`GraphQlClient`] encodes the GraphQL requests and decodes the GraphQL responses as
+//! specified in the GraphQL-over-HTTP specification. Sending the HTTP requests, and receiving the
+//! HTTP responses, is delegated to a [`GraphQlTransport`]:
+//!
+//! - With the `reqwest` feature, the transport is implemented for [`reqwest::Client`].
+//! - With the `tower` feature, any [`tower_service::Service`] accepting HTTP requests can be used
+//!   as the transport, wrapped in a [`ServiceTransport`]. This covers, e.g., _hyper_ clients and
+//!   in-process services, e.g., an _axum_ router.
+
+use std::sync::{
+    Arc,
+    atomic::{AtomicBool, Ordering},
+};
+
+use async_trait::async_trait;
+use bytes::Bytes;
+
+#[cfg(doc)]
+use super::ResponseError;
+use super::{
+    GetRequestOptions, PartialResponse, RequestError, ResponseEnvelope, ResponseResult,
+    accept_header_value, decode_batch_results, graphql_get_request, graphql_post_request,
+    persisted_query_error,
+};
+use crate::{
+    http::{request::IntoRequestParameters, response::ErrorCode},
+    persisted_query::{PERSISTED_QUERY_EXTENSION, PersistedQuery},
+};
+
+/// A GraphQL-over-HTTP client transport, sending the HTTP requests and receiving the HTTP
+/// responses.
+#[async_trait]
+pub trait GraphQlTransport: Send + Sync {
+    /// Send the HTTP request, and receive the response status, headers and raw body bytes.
+    async fn send(&self, request: http::Request<Bytes>) -> Result<ResponseEnvelope, RequestError>;
+}
+
+#[cfg(feature = "reqwest")]
+#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+#[async_trait]
+impl GraphQlTransport for reqwest::Client {
+    async fn send(&self, request: http::Request<Bytes>) -> Result<ResponseEnvelope, RequestError> {
+        let request = reqwest::Request::try_from(request)?;
+        let response = self.execute(request).await?;
+        ResponseEnvelope::from_response(response).await
+    }
+}
+
+/// A [`GraphQlTransport`] backed by a [`tower_service::Service`] accepting HTTP requests.
+///
+/// The service is cloned for every request, so it should be cheap to clone, e.g., a _hyper_
+/// client or a service wrapped in a `tower::buffer::Buffer`.
+///
+/// The HTTP requests are sent with a `ReqBody` body, built from the serialized GraphQL request
+/// bytes. It defaults to [`Full<Bytes>`](http_body_util::Full), accepted, e.g., by _hyper_
+/// clients and _axum_ routers. If the service accepts any body type, the body type must be
+/// specified, e.g., `ServiceTransport::<_, Full<Bytes>>::new(router)`.
+#[cfg(feature = "tower")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tower")))]
+pub struct ServiceTransport<S, ReqBody = http_body_util::Full<Bytes>> {
+    service: S,
+    _body: std::marker::PhantomData<fn(ReqBody)>,
+}
+
+#[cfg(feature = "tower")]
+impl<S, ReqBody> ServiceTransport<S, ReqBody> {
+    /// Create a new transport backed by the given service.
+    pub fn new(service: S) -> Self {
+        Self {
+            service,
+            _body: std::marker::PhantomData,
+        }
+    }
+
+    /// Get a reference to the inner service.
+    pub fn get_ref(&self) -> &S {
+        &self.service
+    }
+
+    /// Consume the transport, returning the inner service.
+    pub fn into_inner(self) -> S {
+        self.service
+    }
+}
+
+// Implemented manually, as deriving them would require the request body type to implement the
+// traits too.
+#[cfg(feature = "tower")]
+impl<S: Clone, ReqBody> Clone for ServiceTransport<S, ReqBody> {
+    fn clone(&self) -> Self {
+        Self::new(self.service.clone())
+    }
+}
+
+#[cfg(feature = "tower")]
+impl<S: std::fmt::Debug, ReqBody> std::fmt::Debug for ServiceTransport<S, ReqBody> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ServiceTransport")
+            .field("service", &self.service)
+            .finish()
+    }
+}
+
+#[cfg(feature = "tower")]
+#[async_trait]
+impl<S, ReqBody, B> GraphQlTransport for ServiceTransport<S, ReqBody>
+where
+    S: tower_service::Service<http::Request<ReqBody>, Response = http::Response<B>>
+        + Clone
+        + Send
+        + Sync,
+    S::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    S::Future: Send,
+    ReqBody: From<Bytes> + Send,
+    B: http_body::Body + Send,
+    B::Data: Send,
+    B::Error: std::fmt::Display,
+{
+    async fn send(&self, request: http::Request<Bytes>) -> Result<ResponseEnvelope, RequestError> {
+        use http_body_util::BodyExt as _;
+
+        let request = request.map(ReqBody::from);
+        let mut service = self.service.clone();
+        std::future::poll_fn(|cx| service.poll_ready(cx))
+            .await
+            .map_err(|err| RequestError::TransportError(err.into()))?;
+        let response = service
+            .call(request)
+            .await
+            .map_err(|err| RequestError::TransportError(err.into()))?;
+
+        let (parts, body) = response.into_parts();
+
+        // Receive the response body.
+        let body = body
+            .collect()
+            .await
+            .map_err(|err| {
+                RequestError::ResponseRecvError(
+                    parts.status,
+                    format!("Error reading response body: {err}"),
+                )
+            })?
+            .to_bytes();
+
+        Ok(ResponseEnvelope {
+            status: parts.status,
+            headers: parts.headers,
+            body,
+        })
+    }
+}
+
+/// A transport-independent GraphQL-over-HTTP client.
+///
+/// The GraphQL requests are sent to the client URL using the `POST` method, unless sent with
+/// [`GraphQlClient::send_graphql_get`].
+#[derive(Debug, Clone)]
+pub struct GraphQlClient<T> {
+    transport: T,
+    url: http::Uri,
+    /// Whether the server replied it does not support automatic persisted queries. Shared by the
+    /// client clones.
+    persisted_queries_not_supported: Arc<AtomicBool>,
+}
+
+impl<T: GraphQlTransport> GraphQlClient<T> {
+    /// Create a new client sending the GraphQL requests to the given URL using the given
+    /// transport.
+    pub fn new(transport: T, url: http::Uri) -> Self {
+        Self {
+            transport,
+            url,
+            persisted_queries_not_supported: Default::default(),
+        }
+    }
+
+    /// Get a reference to the client transport.
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// Get the URL the GraphQL requests are sent to.
+    pub fn url(&self) -> &http::Uri {
+        &self.url
+    }
+
+    /// Runs a GraphQL query, deserializes the body and returns the result.
+    pub async fn send_graphql<ResponseData>(
+        &self,
+        req: impl IntoRequestParameters,
+    ) -> Result<ResponseResult<ResponseData>, RequestError>
+    where
+        ResponseData: serde::de::DeserializeOwned,
+    {
+        // Do not consider partial responses
+        let response = self.send_graphql_partial(req).await?;
+        Ok(response.and_then(PartialResponse::into_result))
+    }
+
+    /// Runs a GraphQL query, deserializes the body and returns the result, keeping the data of
+    /// partial responses.
+    ///
+    /// Unlike [`GraphQlClient::send_graphql`], a response carrying data along with field errors is
+    /// returned as a [`PartialResponse`] instead of a [`ResponseError::Failure`].
+    pub async fn send_graphql_partial<ResponseData>(
+        &self,
+        req: impl IntoRequestParameters,
+    ) -> Result<ResponseResult<PartialResponse<ResponseData>>, RequestError>
+    where
+        ResponseData: serde::de::DeserializeOwned,
+    {
+        let envelope = self.send_graphql_envelope(req).await?;
+        Ok(envelope.decode()?.result)
+    }
+
+    /// Runs a GraphQL query using the `GET` method, deserializes the body and returns the result.
+    ///
+    /// As specified in the GraphQL-over-HTTP specification, only query operations can be sent
+    /// using the `GET` method. With the `graphql-parser` feature enabled, other operations, e.g.,
+    /// mutations, are rejected with a [`RequestError::OperationNotAllowedOverGet`] error. If the
+    /// resulting URL is longer than [`GetRequestOptions::max_url_length`], the request is sent
+    /// using the `POST` method instead.
+    pub async fn send_graphql_get<ResponseData>(
+        &self,
+        req: impl IntoRequestParameters,
+        options: GetRequestOptions,
+    ) -> Result<ResponseResult<ResponseData>, RequestError>
+    where
+        ResponseData: serde::de::DeserializeOwned,
+    {
+        let gql_request = req.into_request_parameters();
+        let envelope = self
+            .transport
+            .send(graphql_get_request(&self.url, &gql_request, options)?)
+            .await?;
+
+        // Do not consider partial responses
+        Ok(envelope
+            .decode()?
+            .result
+            .and_then(PartialResponse::into_result))
+    }
+
+    /// Runs a GraphQL query using automatic persisted queries, deserializes the body and returns
+    /// the result.
+    ///
+    /// The SHA-256 hash of the GraphQL document is sent first, without the document itself. If
+    /// the server does not know the hash, the request is retried with both the document and its
+    /// hash, so the server can store it. If the server does not support persisted queries, the
+    /// request is retried without the hash, and the client, and its clones, send the following
+    /// requests without the hash too.
+    ///
+    /// See the [`persisted_query`](crate::persisted_query) module for more information.
+    pub async fn send_graphql_persisted<ResponseData>(
+        &self,
+        req: impl IntoRequestParameters,
+    ) -> Result<ResponseResult<ResponseData>, RequestError>
+    where
+        ResponseData: serde::de::DeserializeOwned,
+    {
+        let mut gql_request = req.into_request_parameters();
+        if self.persisted_queries_not_supported.load(Ordering::Relaxed) {
+            return self.send_graphql(gql_request).await;
+        }
+
+        PersistedQuery::new(&gql_request.query).insert_into(&mut gql_request.extensions);
+
+        // Send the GraphQL document hash only, i.e., without the `query` parameter
+        let mut hash_only_request =
+            serde_json::to_value(&gql_request).map_err(RequestError::RequestSerializationError)?;
+        if let serde_json::Value::Object(params) = &mut hash_only_request {
+            params.remove("query");
+        }
+        let hash_only_request_body = serde_json::to_vec(&hash_only_request)
+            .map_err(RequestError::RequestSerializationError)?;
+
+        let envelope = self
+            .transport
+            .send(graphql_post_request(
+                &self.url,
+                accept_header_value(),
+                hash_only_request_body,
+            ))
+            .await?;
+
+        let persisted_query_not_supported = {
+            // Do not consider partial responses
+            let result = envelope
+                .decode()?
+                .result
+                .and_then(PartialResponse::into_result);
+
+            match persisted_query_error(&result) {
+                // The server does not know the hash, send the document along with its hash
+                Some(ErrorCode::PersistedQueryNotFound) => false,
+                // The server does not support persisted queries, send the document only
+                Some(ErrorCode::PersistedQueryNotSupported) => true,
+                _ => return Ok(result),
+            }
+        };
+        if persisted_query_not_supported {
+            self.persisted_queries_not_supported
+                .store(true, Ordering::Relaxed);
+            gql_request.extensions.remove(PERSISTED_QUERY_EXTENSION);
+        }
+
+        self.send_graphql(gql_request).await
+    }
+
+    /// Runs a batch of GraphQL queries, deserializes the body and returns the results, in the same
+    /// order as the requests.
+    ///
+    /// Each result is the one [`GraphQlClient::send_graphql`] would return for the request, e.g.,
+    /// a GraphQL response that cannot be deserialized only fails its own result.
+    ///
+    /// If the server does not support batching, i.e., it does not reply with a JSON array of
+    /// GraphQL responses, a [`RequestError::BatchNotSupported`] error is returned. An empty batch
+    /// is not sent, and returns no results.
+    pub async fn send_graphql_batch<ResponseData, Req>(
+        &self,
+        reqs: impl IntoIterator<Item = Req>,
+    ) -> Result<Vec<Result<ResponseResult<ResponseData>, RequestError>>, RequestError>
+    where
+        ResponseData: serde::de::DeserializeOwned,
+        Req: IntoRequestParameters,
+    {
+        let gql_requests = reqs
+            .into_iter()
+            .map(IntoRequestParameters::into_request_parameters)
+            .collect::<Vec<_>>();
+        if gql_requests.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let gql_request_body =
+            serde_json::to_vec(&gql_requests).map_err(RequestError::RequestSerializationError)?;
+        let envelope = self
+            .transport
+            .send(graphql_post_request(
+                &self.url,
+                accept_header_value(),
+                gql_request_body,
+            ))
+            .await?;
+
+        decode_batch_results(&envelope, gql_requests.len())
+    }
+
+    /// Runs a GraphQL query and returns the [`ResponseEnvelope`], i.e., the response status,
+    /// headers and raw body bytes.
+    ///
+    /// The response body is not deserialized, see [`ResponseEnvelope::decode`].
+    pub async fn send_graphql_envelope(
+        &self,
+        req: impl IntoRequestParameters,
+    ) -> Result<ResponseEnvelope, RequestError> {
+        let gql_request = req.into_request_parameters();
+        let gql_request_body =
+            serde_json::to_vec(&gql_request).map_err(RequestError::RequestSerializationError)?;
+
+        self.transport
+            .send(graphql_post_request(
+                &self.url,
+                accept_header_value(),
+                gql_request_body,
+            ))
+            .await
+    }
+}
@@ -3,13 +3,31 @@
 //!
 //! Additionally, this crate provides a GraphQL-over-HTTP client based on this crate types and
 //! [`reqwest`]'s HTTP client. To enable this client extension, use the `reqwest` feature.
+//!
+//! The client protocol logic is transport-independent, see the `GraphQlTransport` trait. To use
+//! any `tower::Service` as the client transport, e.g., a _hyper_ client or an in-process service,
+//! use the `tower` feature.
+//!
+//! To parse GraphQL-over-HTTP requests on the server side, use the `server` feature.
+//!
+//! Automatic persisted queries (APQ) are supported by both the client and the server, see the
+//! `persisted_query` module.
 
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
 mod compat;
 pub mod graphql;
 pub mod http;
+#[cfg(any(feature = "reqwest", feature = "server", feature = "tower"))]
+#[cfg_attr(
+    docsrs,
+    doc(cfg(any(feature = "reqwest", feature = "server", feature = "tower")))
+)]
+pub mod persisted_query;
 
-#[cfg(feature = "reqwest")]
-#[cfg_attr(docsrs, doc(cfg(feature = "reqwest")))]
+#[cfg(any(feature = "reqwest", feature = "tower"))]
+#[cfg_attr(docsrs, doc(cfg(any(feature = "reqwest", feature = "tower"))))]
 pub mod http_client;
+#[cfg(feature = "server")]
+#[cfg_attr(docsrs, doc(cfg(feature = "server")))]
+pub mod server;
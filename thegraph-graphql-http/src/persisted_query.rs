@@ -0,0 +1,320 @@
+//! Automatic persisted queries (APQ).
+//!
+//! With automatic persisted queries, the client first sends the SHA-256 hash of the GraphQL
+//! document in the `persistedQuery` entry of the request `extensions`, without the document itself.
+//! If the server does not know the hash, it replies with a `PERSISTED_QUERY_NOT_FOUND` error, and
+//! the client retries with both the document and its hash, so the server can store it for the
+//! following requests.
+//!
+//! ```json
+//! {
+//!   "extensions": {
+//!     "persistedQuery": {
+//!       "version": 1,
+//!       "sha256Hash": "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38"
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! See the [Apollo APQ protocol](https://github.com/apollographql/apollo-link-persisted-queries#protocol)
+//! for more information.
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    sync::{Mutex, PoisonError},
+};
+
+use sha2::{Digest as _, Sha256};
+
+use crate::graphql::Document;
+
+/// The key of the persisted query entry in the GraphQL request `extensions` map.
+pub const PERSISTED_QUERY_EXTENSION: &str = "persistedQuery";
+
+/// The supported version of the automatic persisted queries protocol.
+pub const PERSISTED_QUERY_VERSION: u32 = 1;
+
+/// The `persistedQuery` entry of the GraphQL request `extensions` map.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Deserialize, serde::Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistedQuery {
+    /// The automatic persisted queries protocol version.
+    pub version: u32,
+
+    /// The lowercase hex-encoded SHA-256 hash of the GraphQL document.
+    pub sha256_hash: String,
+}
+
+impl PersistedQuery {
+    /// Create a new persisted query entry for the given GraphQL document.
+    pub fn new(query: &Document) -> Self {
+        Self {
+            version: PERSISTED_QUERY_VERSION,
+            sha256_hash: sha256_hash(query.as_str()),
+        }
+    }
+
+    /// Get the persisted query entry from the GraphQL request `extensions` map.
+    ///
+    /// Returns `None` if the entry is not present, and an error if it is not valid.
+    pub fn from_extensions(
+        extensions: &serde_json::Map<String, serde_json::Value>,
+    ) -> Option<Result<Self, serde_json::Error>> {
+        extensions
+            .get(PERSISTED_QUERY_EXTENSION)
+            .map(|value| serde_json::from_value(value.clone()))
+    }
+
+    /// Insert the persisted query entry into the GraphQL request `extensions` map.
+    pub fn insert_into(&self, extensions: &mut serde_json::Map<String, serde_json::Value>) {
+        extensions.insert(
+            PERSISTED_QUERY_EXTENSION.to_string(),
+            serde_json::to_value(self).expect("persisted query to be serializable"),
+        );
+    }
+
+    /// Check if the hash matches the given GraphQL document.
+    pub fn matches(&self, query: &str) -> bool {
+        self.sha256_hash.eq_ignore_ascii_case(&sha256_hash(query))
+    }
+}
+
+/// Compute the lowercase hex-encoded SHA-256 hash of the given GraphQL document.
+pub fn sha256_hash(query: &str) -> String {
+    format!("{:x}", Sha256::digest(query.as_bytes()))
+}
+
+/// A store of persisted GraphQL documents, indexed by their SHA-256 hash.
+pub trait PersistedQueryStore: Send + Sync {
+    /// Get the GraphQL document with the given hash, if known.
+    fn get(&self, sha256_hash: &str) -> Option<Document>;
+
+    /// Store the GraphQL document with the given hash.
+    fn insert(&self, sha256_hash: String, query: Document);
+}
+
+/// A bounded in-memory [`PersistedQueryStore`].
+///
+/// The store is bounded by the number of documents and, optionally, by their total size in bytes,
+/// see [`InMemoryPersistedQueryStore::with_max_bytes`]. When the store is full, the least recently
+/// used documents are evicted. Documents larger than the total size bound are not stored.
+///
+/// Without a total size bound, the store memory usage is only bounded by the number of documents
+/// times the size of the largest document. In that case, callers must cap the request body size.
+#[derive(Debug)]
+pub struct InMemoryPersistedQueryStore {
+    capacity: usize,
+    max_bytes: usize,
+    inner: Mutex<LruCache>,
+}
+
+/// The least recently used cache backing the [`InMemoryPersistedQueryStore`].
+#[derive(Debug, Default)]
+struct LruCache {
+    /// The stored documents, and their last access tick.
+    entries: HashMap<String, (Document, u64)>,
+    /// The stored documents' hashes, indexed by their last access tick.
+    recency: BTreeMap<u64, String>,
+    /// The access counter.
+    tick: u64,
+    /// The total size of the stored documents, in bytes.
+    size: usize,
+}
+
+impl LruCache {
+    /// Mark the entry as the most recently used, and return its document.
+    fn touch(&mut self, sha256_hash: &str) -> Option<&Document> {
+        self.tick += 1;
+        let (query, last_access) = self.entries.get_mut(sha256_hash)?;
+        let hash = self
+            .recency
+            .remove(last_access)
+            .expect("recency index to be consistent");
+        *last_access = self.tick;
+        self.recency.insert(self.tick, hash);
+        Some(query)
+    }
+
+    /// Evict the least recently used entry, if any.
+    fn evict(&mut self) {
+        if let Some((_, evicted)) = self.recency.pop_first() {
+            if let Some((query, _)) = self.entries.remove(&evicted) {
+                self.size -= query.as_str().len();
+            }
+        }
+    }
+}
+
+impl InMemoryPersistedQueryStore {
+    /// Create a new store holding up to `capacity` documents.
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            max_bytes: usize::MAX,
+            inner: Mutex::new(LruCache::default()),
+        }
+    }
+
+    /// Bound the total size of the stored documents to `max_bytes` bytes.
+    pub fn with_max_bytes(mut self, max_bytes: usize) -> Self {
+        self.max_bytes = max_bytes;
+        self
+    }
+
+    /// The maximum number of documents the store holds.
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    /// The maximum total size of the stored documents, in bytes.
+    pub fn max_bytes(&self) -> usize {
+        self.max_bytes
+    }
+
+    /// The number of documents in the store.
+    pub fn len(&self) -> usize {
+        self.inner
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entries
+            .len()
+    }
+
+    /// Check if the store is empty.
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+impl PersistedQueryStore for InMemoryPersistedQueryStore {
+    fn get(&self, sha256_hash: &str) -> Option<Document> {
+        let mut cache = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.touch(sha256_hash).cloned()
+    }
+
+    fn insert(&self, sha256_hash: String, query: Document) {
+        let query_size = query.as_str().len();
+        if self.capacity == 0 || query_size > self.max_bytes {
+            return;
+        }
+
+        let mut cache = self.inner.lock().unwrap_or_else(PoisonError::into_inner);
+        if cache.touch(&sha256_hash).is_some() {
+            return;
+        }
+
+        // Evict the least recently used documents until the new document fits
+        while !cache.entries.is_empty()
+            && (cache.entries.len() >= self.capacity || cache.size + query_size > self.max_bytes)
+        {
+            cache.evict();
+        }
+
+        let tick = cache.tick;
+        cache.size += query_size;
+        cache.recency.insert(tick, sha256_hash.clone());
+        cache.entries.insert(sha256_hash, (query, tick));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{InMemoryPersistedQueryStore, PersistedQuery, PersistedQueryStore, sha256_hash};
+    use crate::graphql::Document;
+
+    #[test]
+    fn compute_sha256_hash() {
+        //* Then
+        assert_eq!(
+            sha256_hash("{__typename}"),
+            "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38"
+        );
+    }
+
+    #[test]
+    fn persisted_query_extension_round_trip() {
+        //* Given
+        let query = Document::new("{__typename}".to_string());
+        let persisted_query = PersistedQuery::new(&query);
+
+        let mut extensions = serde_json::Map::new();
+
+        //* When
+        persisted_query.insert_into(&mut extensions);
+
+        //* Then
+        assert_eq!(
+            serde_json::Value::Object(extensions.clone()),
+            serde_json::json!({
+                "persistedQuery": {
+                    "version": 1,
+                    "sha256Hash": "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38"
+                }
+            })
+        );
+        let decoded = PersistedQuery::from_extensions(&extensions)
+            .expect("entry to be present")
+            .expect("entry to be valid");
+        assert_eq!(decoded, persisted_query);
+        assert!(decoded.matches("{__typename}"));
+        assert!(!decoded.matches("{ __typename }"));
+    }
+
+    #[test]
+    fn in_memory_store_evicts_least_recently_used() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(2);
+        let doc = |query: &str| Document::new(query.to_string());
+
+        //* When
+        store.insert("a".to_string(), doc("{ a }"));
+        store.insert("b".to_string(), doc("{ b }"));
+        // Access `a` so `b` becomes the least recently used
+        assert_eq!(store.get("a"), Some(doc("{ a }")));
+        store.insert("c".to_string(), doc("{ c }"));
+
+        //* Then
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get("a"), Some(doc("{ a }")));
+        assert_eq!(store.get("b"), None);
+        assert_eq!(store.get("c"), Some(doc("{ c }")));
+    }
+
+    #[test]
+    fn in_memory_store_evicts_until_under_max_bytes() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(16).with_max_bytes(16);
+        let doc = |query: &str| Document::new(query.to_string());
+
+        //* When
+        store.insert("a".to_string(), doc("{ a }"));
+        store.insert("b".to_string(), doc("{ b }"));
+        store.insert("c".to_string(), doc("{ c }"));
+        // Evicts both `a` and `b`
+        store.insert("d".to_string(), doc("{ d { e } }"));
+        // Larger than the total size bound, not stored
+        store.insert("f".to_string(), doc("{ f { g { h } } }"));
+
+        //* Then
+        assert_eq!(store.len(), 2);
+        assert_eq!(store.get("a"), None);
+        assert_eq!(store.get("b"), None);
+        assert_eq!(store.get("c"), Some(doc("{ c }")));
+        assert_eq!(store.get("d"), Some(doc("{ d { e } }")));
+        assert_eq!(store.get("f"), None);
+    }
+
+    #[test]
+    fn in_memory_store_with_zero_capacity() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(0);
+
+        //* When
+        store.insert("a".to_string(), Document::new("{ a }".to_string()));
+
+        //* Then
+        assert!(store.is_empty());
+        assert_eq!(store.get("a"), None);
+    }
+}
@@ -0,0 +1,899 @@
+//! GraphQL-over-HTTP server-side request parsing.
+//!
+//! This module provides the building blocks to receive GraphQL-over-HTTP requests, as specified in
+//! the [GraphQL-over-HTTP specification](https://graphql.github.io/graphql-over-http/draft/):
+//!
+//! - Parsing of `GET` requests' query string, see [`parse_get_request`].
+//! - Parsing of `POST` requests' `application/json` body, see [`parse_post_request`].
+//! - Content negotiation of the response media type based on the `Accept` header, see
+//!   [`negotiate_response_media_type`].
+//! - The status codes for malformed requests, see [`RequestRejection::status_code`].
+//!
+//! The [`parse_request`] function combines all of them for an [`http::Request`].
+//!
+//! ```rust
+//! use thegraph_graphql_http::server::{ResponseMediaType, parse_request};
+//!
+//! let request = http::Request::post("/graphql")
+//!     .header(http::header::CONTENT_TYPE, "application/json")
+//!     .header(http::header::ACCEPT, "application/graphql-response+json")
+//!     .body(r#"{"query": "{ a }"}"#)
+//!     .unwrap();
+//!
+//! let request = parse_request(&request).unwrap();
+//! assert_eq!(request.parameters.query.as_str(), "{ a }");
+//! assert_eq!(request.response_media_type, ResponseMediaType::GraphqlResponseJson);
+//! ```
+
+use http::{HeaderMap, Method, StatusCode, header};
+
+use crate::{
+    http::{
+        content_type::{ContentType, is_utf8_charset},
+        request::{GRAPHQL_REQUEST_MEDIA_TYPE, InvalidRequestParameters, RequestParameters},
+        response::{
+            Error, ErrorCode, GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE, GRAPHQL_RESPONSE_MEDIA_TYPE,
+        },
+    },
+    persisted_query::{PERSISTED_QUERY_VERSION, PersistedQuery, PersistedQueryStore},
+};
+
+/// The reasons a GraphQL-over-HTTP request is rejected before execution.
+#[derive(Debug, thiserror::Error)]
+pub enum RequestRejection {
+    /// The request method is neither `GET` nor `POST`.
+    #[error("Method not allowed: {0}")]
+    MethodNotAllowed(Method),
+
+    /// The `GET` request operation is not a query operation, e.g., a mutation.
+    ///
+    /// Only detected with the `graphql-parser` feature enabled.
+    #[error("GraphQL {0} operations cannot be executed using the GET method")]
+    OperationNotAllowedOverGet(&'static str),
+
+    /// The `POST` request `Content-Type` header is missing, or it is not `application/json`.
+    #[error("Unsupported media type: {0}")]
+    UnsupportedMediaType(String),
+
+    /// None of the media types in the `Accept` header is supported.
+    #[error("Not acceptable: {0}")]
+    NotAcceptable(String),
+
+    /// The `POST` request body is not a JSON object, or a `GET` request JSON-encoded parameter
+    /// is not valid JSON.
+    #[error("Invalid JSON: {0}")]
+    InvalidJson(#[source] serde_json::Error),
+
+    /// A `GET` request query string parameter is present more than once.
+    #[error("Duplicate query string parameter: {0}")]
+    DuplicateParameter(String),
+
+    /// The request parameters are not valid.
+    #[error("Invalid request parameters: {0}")]
+    InvalidParameters(#[from] InvalidRequestParameters),
+
+    /// The `persistedQuery` request extension is not valid, or its version is not supported.
+    #[error("Invalid persisted query: {0}")]
+    InvalidPersistedQuery(String),
+
+    /// The `persistedQuery` request extension hash does not match the request GraphQL document.
+    #[error("Persisted query hash mismatch")]
+    PersistedQueryHashMismatch,
+
+    /// The `persistedQuery` request extension hash is unknown, and the request does not contain
+    /// the GraphQL document.
+    #[error("PersistedQueryNotFound")]
+    PersistedQueryNotFound,
+
+    /// The request contains a `persistedQuery` extension, without the GraphQL document, but
+    /// persisted queries are not supported.
+    #[error("PersistedQueryNotSupported")]
+    PersistedQueryNotSupported,
+}
+
+impl RequestRejection {
+    /// The HTTP status code to reply with, for the given negotiated response media type.
+    ///
+    /// As specified in the GraphQL-over-HTTP specification, a request that is not a well-formed
+    /// GraphQL-over-HTTP request SHOULD be replied with a `400 Bad Request` status code,
+    /// independent of the response media type.
+    ///
+    /// The automatic persisted queries errors, i.e., [`RequestRejection::PersistedQueryNotFound`]
+    /// and [`RequestRejection::PersistedQueryNotSupported`], are GraphQL request errors, and their
+    /// status code depends on the response media type, see
+    /// [`ResponseMediaType::request_error_status_code`]. Clients relying on the legacy
+    /// `application/json` media type expect a `200 OK` status code with the error in `errors`.
+    ///
+    /// The response media type is negotiated before any of these errors is detected, so it is
+    /// available with [`negotiate_response_media_type`].
+    pub fn status_code(&self, response_media_type: ResponseMediaType) -> StatusCode {
+        match self {
+            Self::MethodNotAllowed(_) | Self::OperationNotAllowedOverGet(_) => {
+                StatusCode::METHOD_NOT_ALLOWED
+            }
+            Self::UnsupportedMediaType(_) => StatusCode::UNSUPPORTED_MEDIA_TYPE,
+            Self::NotAcceptable(_) => StatusCode::NOT_ACCEPTABLE,
+            Self::InvalidJson(_)
+            | Self::DuplicateParameter(_)
+            | Self::InvalidParameters(_)
+            | Self::InvalidPersistedQuery(_)
+            | Self::PersistedQueryHashMismatch => StatusCode::BAD_REQUEST,
+            Self::PersistedQueryNotFound | Self::PersistedQueryNotSupported => {
+                response_media_type.request_error_status_code()
+            }
+        }
+    }
+
+    /// The well-known error code of the rejection, if any.
+    pub fn error_code(&self) -> Option<ErrorCode> {
+        match self {
+            Self::PersistedQueryNotFound => Some(ErrorCode::PersistedQueryNotFound),
+            Self::PersistedQueryNotSupported => Some(ErrorCode::PersistedQueryNotSupported),
+            _ => None,
+        }
+    }
+
+    /// Convert the rejection into a GraphQL response [`Error`], with its error code, if any.
+    pub fn to_error(&self) -> Error {
+        let error = Error {
+            message: self.to_string(),
+            locations: vec![],
+            path: vec![],
+            extensions: Default::default(),
+        };
+        match self.error_code() {
+            Some(code) => error.with_code(code),
+            None => error,
+        }
+    }
+}
+
+/// The media type of a GraphQL-over-HTTP response.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResponseMediaType {
+    /// The `application/graphql-response+json` media type.
+    GraphqlResponseJson,
+    /// The legacy `application/json` media type.
+    Json,
+}
+
+impl ResponseMediaType {
+    /// Get the media type string, suitable for the response `Content-Type` header.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::GraphqlResponseJson => GRAPHQL_RESPONSE_MEDIA_TYPE,
+            Self::Json => GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE,
+        }
+    }
+
+    /// Get the response status code for a well-formed request that failed with a GraphQL request
+    /// error, i.e., a response without `data`.
+    ///
+    /// See the sections [6.4.1 application/json](https://graphql.github.io/graphql-over-http/draft/#sec-application-json)
+    /// and [6.4.2 application/graphql-response+json](https://graphql.github.io/graphql-over-http/draft/#sec-application-graphql-response-json)
+    /// of the GraphQL-over-HTTP specification.
+    pub fn request_error_status_code(&self) -> StatusCode {
+        match self {
+            Self::GraphqlResponseJson => StatusCode::BAD_REQUEST,
+            Self::Json => StatusCode::OK,
+        }
+    }
+}
+
+impl std::fmt::Display for ResponseMediaType {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A parsed GraphQL-over-HTTP request.
+#[derive(Debug, Clone)]
+pub struct GraphqlRequest {
+    /// The GraphQL request parameters.
+    pub parameters: RequestParameters,
+
+    /// The negotiated response media type.
+    pub response_media_type: ResponseMediaType,
+}
+
+/// Parse a GraphQL-over-HTTP request, negotiating the response media type.
+///
+/// Only `GET` and `POST` requests are accepted, as specified in the section
+/// [6.1 Methods](https://graphql.github.io/graphql-over-http/draft/#sec-Methods) of the
+/// GraphQL-over-HTTP specification.
+///
+/// With the `graphql-parser` feature enabled, `GET` requests executing a non-query operation, e.g.,
+/// a mutation, are rejected with [`RequestRejection::OperationNotAllowedOverGet`]. Otherwise, this
+/// check is skipped and left to the caller.
+///
+/// Requests relying on automatic persisted queries, i.e., without the GraphQL document, are
+/// rejected with [`RequestRejection::PersistedQueryNotSupported`]. See
+/// [`parse_request_with_persisted_queries`].
+pub fn parse_request<B: AsRef<[u8]>>(
+    req: &http::Request<B>,
+) -> Result<GraphqlRequest, RequestRejection> {
+    parse_request_inner(req, None)
+}
+
+/// Parse a GraphQL-over-HTTP request, resolving automatic persisted queries with the given
+/// store, and negotiating the response media type.
+///
+/// If the request contains a `persistedQuery` extension without the GraphQL document, the document
+/// is looked up in the store by its hash. If the request contains both, the hash is verified, and
+/// the document is stored.
+///
+/// See the [`persisted_query`](crate::persisted_query) module for more information.
+pub fn parse_request_with_persisted_queries<B, S>(
+    req: &http::Request<B>,
+    store: &S,
+) -> Result<GraphqlRequest, RequestRejection>
+where
+    B: AsRef<[u8]>,
+    S: PersistedQueryStore + ?Sized,
+{
+    parse_request_inner(req, Some(&|raw| resolve_persisted_query(raw, store)))
+}
+
+/// The raw parameters of a GraphQL-over-HTTP request, before validation.
+#[derive(Debug, Default)]
+struct RawRequestParameters {
+    query: Option<serde_json::Value>,
+    operation_name: Option<serde_json::Value>,
+    variables: Option<serde_json::Value>,
+    extensions: Option<serde_json::Value>,
+}
+
+impl RawRequestParameters {
+    /// Validate the raw request parameters.
+    fn into_parameters(self) -> Result<RequestParameters, RequestRejection> {
+        let params = RequestParameters::from_values(
+            self.query,
+            self.operation_name,
+            self.variables,
+            self.extensions,
+        )?;
+        Ok(params)
+    }
+
+    /// Get the `persistedQuery` request extension, if present.
+    fn persisted_query(&self) -> Result<Option<PersistedQuery>, RequestRejection> {
+        let Some(serde_json::Value::Object(extensions)) = &self.extensions else {
+            return Ok(None);
+        };
+        let Some(persisted_query) = PersistedQuery::from_extensions(extensions) else {
+            return Ok(None);
+        };
+
+        let persisted_query = persisted_query
+            .map_err(|err| RequestRejection::InvalidPersistedQuery(err.to_string()))?;
+        if persisted_query.version != PERSISTED_QUERY_VERSION {
+            return Err(RequestRejection::InvalidPersistedQuery(format!(
+                "unsupported version {}",
+                persisted_query.version
+            )));
+        }
+        Ok(Some(persisted_query))
+    }
+
+    /// Check if the request does not contain the GraphQL document.
+    fn is_missing_query(&self) -> bool {
+        matches!(self.query, None | Some(serde_json::Value::Null))
+    }
+}
+
+/// A function resolving the GraphQL document of automatic persisted queries requests.
+type PersistedQueryResolver<'a> =
+    &'a dyn Fn(&mut RawRequestParameters) -> Result<(), RequestRejection>;
+
+fn parse_request_inner<B: AsRef<[u8]>>(
+    req: &http::Request<B>,
+    resolve_persisted_query: Option<PersistedQueryResolver<'_>>,
+) -> Result<GraphqlRequest, RequestRejection> {
+    let method = req.method();
+    let mut raw = match *method {
+        Method::GET => parse_get_values(req.uri().query().unwrap_or_default())?,
+        Method::POST => parse_post_values(req.headers(), req.body().as_ref())?,
+        ref method => return Err(RequestRejection::MethodNotAllowed(method.clone())),
+    };
+    let response_media_type = negotiate_response_media_type(req.headers())?;
+
+    match resolve_persisted_query {
+        Some(resolve) => resolve(&mut raw)?,
+        None if raw.is_missing_query() && raw.persisted_query()?.is_some() => {
+            return Err(RequestRejection::PersistedQueryNotSupported);
+        }
+        None => {}
+    }
+
+    let parameters = raw.into_parameters()?;
+    if method == Method::GET {
+        check_get_operation(&parameters)?;
+    }
+
+    Ok(GraphqlRequest {
+        parameters,
+        response_media_type,
+    })
+}
+
+/// Resolve the GraphQL document of an automatic persisted query request with the given store.
+fn resolve_persisted_query<S>(
+    raw: &mut RawRequestParameters,
+    store: &S,
+) -> Result<(), RequestRejection>
+where
+    S: PersistedQueryStore + ?Sized,
+{
+    let Some(persisted_query) = raw.persisted_query()? else {
+        return Ok(());
+    };
+
+    // The hash is compared case-insensitively, store and look it up in its canonical form
+    let hash = persisted_query.sha256_hash.to_ascii_lowercase();
+
+    match &raw.query {
+        // The request contains the GraphQL document, verify and store it
+        Some(serde_json::Value::String(query)) => {
+            if !persisted_query.matches(query) {
+                return Err(RequestRejection::PersistedQueryHashMismatch);
+            }
+            store.insert(hash, query.clone().into());
+        }
+        // The request does not contain the GraphQL document, look it up
+        None | Some(serde_json::Value::Null) => {
+            let query = store
+                .get(&hash)
+                .ok_or(RequestRejection::PersistedQueryNotFound)?;
+            raw.query = Some(serde_json::Value::String(query.to_string()));
+        }
+        // Invalid `query` parameter, rejected on validation
+        Some(_) => {}
+    }
+
+    Ok(())
+}
+
+/// Parse the request parameters of a `GET` request from its (URL-encoded) query string.
+///
+/// The `variables` and `extensions` parameters, if present, are JSON-encoded.
+///
+/// See the section [6.2.1 GET](https://graphql.github.io/graphql-over-http/draft/#sec-GET) of the
+/// GraphQL-over-HTTP specification.
+///
+/// With the `graphql-parser` feature enabled, non-query operations, e.g., mutations, are rejected
+/// with [`RequestRejection::OperationNotAllowedOverGet`]. Otherwise, this check is skipped and
+/// left to the caller.
+pub fn parse_get_request(query: &str) -> Result<RequestParameters, RequestRejection> {
+    let parameters = parse_get_values(query)?.into_parameters()?;
+    check_get_operation(&parameters)?;
+    Ok(parameters)
+}
+
+/// Check that the operation to execute can be executed using the `GET` method.
+///
+/// > GET requests MUST NOT be used for executing mutation operations.
+fn check_get_operation(parameters: &RequestParameters) -> Result<(), RequestRejection> {
+    #[cfg(feature = "graphql-parser")]
+    if let Some(operation) = crate::compat::compat_graphql_parser::non_query_operation(parameters) {
+        return Err(RequestRejection::OperationNotAllowedOverGet(operation));
+    }
+    #[cfg(not(feature = "graphql-parser"))]
+    let _ = parameters;
+
+    Ok(())
+}
+
+fn parse_get_values(query: &str) -> Result<RawRequestParameters, RequestRejection> {
+    let mut query_param = None;
+    let mut operation_name = None;
+    let mut variables = None;
+    let mut extensions = None;
+
+    for (name, value) in form_urlencoded::parse(query.as_bytes()) {
+        let param = match name.as_ref() {
+            "query" => &mut query_param,
+            "operationName" => &mut operation_name,
+            "variables" => &mut variables,
+            "extensions" => &mut extensions,
+            // Ignore unknown query string parameters
+            _ => continue,
+        };
+        if param.is_some() {
+            return Err(RequestRejection::DuplicateParameter(name.into_owned()));
+        }
+        *param = Some(value.into_owned());
+    }
+
+    let parse_json = |value: Option<String>| {
+        value
+            .map(|value| serde_json::from_str(&value))
+            .transpose()
+            .map_err(RequestRejection::InvalidJson)
+    };
+
+    Ok(RawRequestParameters {
+        query: query_param.map(serde_json::Value::String),
+        operation_name: operation_name.map(serde_json::Value::String),
+        variables: parse_json(variables)?,
+        extensions: parse_json(extensions)?,
+    })
+}
+
+/// Parse the request parameters of a `POST` request from its `application/json` body.
+///
+/// See the section [6.2.2 POST](https://graphql.github.io/graphql-over-http/draft/#sec-POST) of
+/// the GraphQL-over-HTTP specification.
+pub fn parse_post_request(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<RequestParameters, RequestRejection> {
+    parse_post_values(headers, body)?.into_parameters()
+}
+
+fn parse_post_values(
+    headers: &HeaderMap,
+    body: &[u8],
+) -> Result<RawRequestParameters, RequestRejection> {
+    // > A client MUST indicate the media type of a request body using the `Content-Type` header
+    // > [...] If the client does not supply a `Content-Type` header with a POST request, the
+    // > server SHOULD reject the request using the appropriate 4xx status code.
+    let content_type = headers
+        .get(header::CONTENT_TYPE)
+        .ok_or_else(|| RequestRejection::UnsupportedMediaType("missing".to_string()))?;
+    let content_type_str = content_type.to_str().unwrap_or_default();
+    let parsed = ContentType::parse(content_type_str);
+    if !parsed.is(GRAPHQL_REQUEST_MEDIA_TYPE)
+        || parsed.charset().is_some_and(|c| !is_utf8_charset(c))
+    {
+        return Err(RequestRejection::UnsupportedMediaType(
+            String::from_utf8_lossy(content_type.as_bytes()).into_owned(),
+        ));
+    }
+
+    // Distinguish malformed JSON from well-formed JSON with invalid request parameters
+    let value =
+        serde_json::from_slice::<serde_json::Value>(body).map_err(RequestRejection::InvalidJson)?;
+    let serde_json::Value::Object(mut params) = value else {
+        return Err(RequestRejection::InvalidJson(serde::de::Error::custom(
+            "the request body must be a JSON object",
+        )));
+    };
+
+    Ok(RawRequestParameters {
+        query: params.remove("query"),
+        operation_name: params.remove("operationName"),
+        variables: params.remove("variables"),
+        extensions: params.remove("extensions"),
+    })
+}
+
+/// Negotiate the response media type based on the request `Accept` header.
+///
+/// The highest priority supported media type is selected. Wildcard media ranges, e.g., `*/*`,
+/// select `application/graphql-response+json`. If the `Accept` header is missing, the legacy
+/// `application/json` media type is selected.
+///
+/// See the section [6.3 Accept](https://graphql.github.io/graphql-over-http/draft/#sec-Accept)
+/// of the GraphQL-over-HTTP specification.
+pub fn negotiate_response_media_type(
+    headers: &HeaderMap,
+) -> Result<ResponseMediaType, RequestRejection> {
+    let mut accept = headers.get_all(header::ACCEPT).iter().peekable();
+
+    // > If the client does not supply an `Accept` header, the server SHOULD treat the request as
+    // > if it had `Accept: application/json`.
+    if accept.peek().is_none() {
+        return Ok(ResponseMediaType::Json);
+    }
+
+    let mut selected: Option<(f32, ResponseMediaType)> = None;
+    let mut accept_values = Vec::new();
+    for value in accept {
+        let value = value.to_str().unwrap_or_default();
+        accept_values.push(value);
+
+        for media_range in value.split(',') {
+            let media_range = ContentType::parse(media_range);
+            let media_type = if media_range.is(GRAPHQL_RESPONSE_MEDIA_TYPE)
+                || media_range.is("application/*")
+                || media_range.is("*/*")
+            {
+                ResponseMediaType::GraphqlResponseJson
+            } else if media_range.is(GRAPHQL_LEGACY_RESPONSE_MEDIA_TYPE) {
+                ResponseMediaType::Json
+            } else {
+                continue;
+            };
+
+            // Only UTF-8 encoding is supported
+            if media_range.charset().is_some_and(|c| !is_utf8_charset(c)) {
+                continue;
+            }
+
+            // Media ranges with an invalid or zero quality value are not acceptable
+            let quality = match media_range.param("q") {
+                Some(q) => match q.parse::<f32>() {
+                    Ok(q) if q > 0.0 && q <= 1.0 => q,
+                    _ => continue,
+                },
+                None => 1.0,
+            };
+
+            // Keep the first media type with the highest quality
+            if selected.is_none_or(|(selected, _)| quality > selected) {
+                selected = Some((quality, media_type));
+            }
+        }
+    }
+
+    selected
+        .map(|(_, media_type)| media_type)
+        .ok_or_else(|| RequestRejection::NotAcceptable(accept_values.join(", ")))
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_matches::assert_matches;
+    use http::{HeaderMap, HeaderValue, StatusCode, header};
+
+    use super::{
+        RequestRejection, ResponseMediaType, negotiate_response_media_type, parse_get_request,
+        parse_post_request, parse_request, parse_request_with_persisted_queries,
+    };
+    use crate::{
+        http::{request::InvalidRequestParameters, response::ErrorCode},
+        persisted_query::{InMemoryPersistedQueryStore, PersistedQueryStore as _},
+    };
+
+    /// Create a header map with the given `Accept` header value.
+    fn accept(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::ACCEPT, HeaderValue::from_static(value));
+        headers
+    }
+
+    /// Create a `POST` request with the given JSON body.
+    fn post(body: serde_json::Value) -> http::Request<Vec<u8>> {
+        http::Request::post("/graphql")
+            .header(header::CONTENT_TYPE, "application/json")
+            .body(serde_json::to_vec(&body).unwrap())
+            .unwrap()
+    }
+
+    /// Create a header map with the given `Content-Type` header value.
+    fn content_type(value: &'static str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(header::CONTENT_TYPE, HeaderValue::from_static(value));
+        headers
+    }
+
+    #[test]
+    fn parse_get_request_query_string() {
+        //* Given
+        let query = "query=query%20Film(%24id%3A%20ID)%20%7B%20film(id%3A%20%24id)%20%7B%20title%20%7D%20%7D\
+            &operationName=Film&variables=%7B%22id%22%3A%221%22%7D&extensions=%7B%7D&unknown=1";
+
+        //* When
+        let params = parse_get_request(query);
+
+        //* Then
+        assert_matches!(params, Ok(params) => {
+            assert_eq!(params.query.as_str(), "query Film($id: ID) { film(id: $id) { title } }");
+            assert_eq!(params.operation_name.as_deref(), Some("Film"));
+            assert_eq!(params.variables["id"], "1");
+            assert!(params.extensions.is_empty());
+        });
+    }
+
+    #[test]
+    fn fail_parse_invalid_get_requests() {
+        //* Then
+        assert_matches!(
+            parse_get_request(""),
+            Err(RequestRejection::InvalidParameters(
+                InvalidRequestParameters::MissingQuery
+            ))
+        );
+        assert_matches!(
+            parse_get_request("query=%7B%20a%20%7D&variables=%7Bnot-json"),
+            Err(RequestRejection::InvalidJson(_))
+        );
+        assert_matches!(
+            parse_get_request("query=%7B%20a%20%7D&variables=%5B%5D"),
+            Err(RequestRejection::InvalidParameters(
+                InvalidRequestParameters::InvalidVariables
+            ))
+        );
+        assert_matches!(
+            parse_get_request("query=a&query=b"),
+            Err(RequestRejection::DuplicateParameter(name)) => {
+                assert_eq!(name, "query");
+            }
+        );
+    }
+
+    #[cfg(feature = "graphql-parser")]
+    #[test]
+    fn fail_parse_get_requests_with_mutations() {
+        //* Given
+        let mutation = http::Request::get("/graphql?query=mutation%20%7B%20a%20%7D")
+            .body(Vec::new())
+            .unwrap();
+        let named = "query=query%20A%20%7B%20a%20%7D%20mutation%20B%20%7B%20b%20%7D";
+
+        //* Then
+        assert_matches!(
+            parse_request(&mutation),
+            Err(err @ RequestRejection::OperationNotAllowedOverGet("mutation")) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::METHOD_NOT_ALLOWED
+                );
+            }
+        );
+        assert_matches!(
+            parse_get_request(&format!("{named}&operationName=B")),
+            Err(RequestRejection::OperationNotAllowedOverGet("mutation"))
+        );
+        assert_matches!(
+            parse_get_request(&format!("{named}&operationName=A")),
+            Ok(_)
+        );
+    }
+
+    #[test]
+    fn parse_post_request_json_body() {
+        //* Given
+        let headers = content_type("application/json; charset=utf-8");
+        let body = br#"{"query": "{ a }", "variables": {"id": 1}}"#;
+
+        //* When
+        let params = parse_post_request(&headers, body);
+
+        //* Then
+        assert_matches!(params, Ok(params) => {
+            assert_eq!(params.query.as_str(), "{ a }");
+            assert_eq!(params.variables["id"], 1);
+        });
+    }
+
+    #[test]
+    fn fail_parse_invalid_post_requests() {
+        //* Given
+        let json = content_type("application/json");
+        let body = br#"{"query": "{ a }"}"#;
+
+        //* Then
+        assert_matches!(
+            parse_post_request(&HeaderMap::new(), body),
+            Err(err @ RequestRejection::UnsupportedMediaType(_)) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE
+                );
+            }
+        );
+        assert_matches!(
+            parse_post_request(&content_type("text/plain"), body),
+            Err(RequestRejection::UnsupportedMediaType(_))
+        );
+        assert_matches!(
+            parse_post_request(&content_type("application/json; charset=latin1"), body),
+            Err(RequestRejection::UnsupportedMediaType(_))
+        );
+        assert_matches!(
+            parse_post_request(&json, b"{not-json"),
+            Err(err @ RequestRejection::InvalidJson(_)) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::BAD_REQUEST
+                );
+            }
+        );
+        assert_matches!(
+            parse_post_request(&json, b"[]"),
+            Err(RequestRejection::InvalidJson(_))
+        );
+        assert_matches!(
+            parse_post_request(&json, br#"{"query": ["{ a }"]}"#),
+            Err(err @ RequestRejection::InvalidParameters(InvalidRequestParameters::InvalidQuery)) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::BAD_REQUEST
+                );
+            }
+        );
+    }
+
+    #[test]
+    fn negotiate_response_media_types() {
+        //* Then
+        assert_matches!(
+            negotiate_response_media_type(&HeaderMap::new()),
+            Ok(ResponseMediaType::Json)
+        );
+        assert_matches!(
+            negotiate_response_media_type(&accept(
+                "application/graphql-response+json; charset=utf-8, application/json; charset=utf-8"
+            )),
+            Ok(ResponseMediaType::GraphqlResponseJson)
+        );
+        assert_matches!(
+            negotiate_response_media_type(&accept(
+                "application/graphql-response+json;q=0.5, application/json"
+            )),
+            Ok(ResponseMediaType::Json)
+        );
+        assert_matches!(
+            negotiate_response_media_type(&accept("text/html, */*;q=0.1")),
+            Ok(ResponseMediaType::GraphqlResponseJson)
+        );
+        assert_matches!(
+            negotiate_response_media_type(&accept("application/json;q=0")),
+            Err(err @ RequestRejection::NotAcceptable(_)) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::NOT_ACCEPTABLE
+                );
+            }
+        );
+        assert_matches!(
+            negotiate_response_media_type(&accept("application/json; charset=utf-16")),
+            Err(RequestRejection::NotAcceptable(_))
+        );
+    }
+
+    #[test]
+    fn parse_http_requests() {
+        //* Given
+        let get = http::Request::get("/graphql?query=%7B%20a%20%7D")
+            .header(header::ACCEPT, "application/graphql-response+json")
+            .body(Vec::new())
+            .unwrap();
+        let put = http::Request::put("/graphql")
+            .body(br#"{"query": "{ a }"}"#.to_vec())
+            .unwrap();
+
+        //* Then
+        assert_matches!(parse_request(&get), Ok(request) => {
+            assert_eq!(request.parameters.query.as_str(), "{ a }");
+            assert_eq!(request.response_media_type, ResponseMediaType::GraphqlResponseJson);
+            assert_eq!(
+                request.response_media_type.request_error_status_code(),
+                StatusCode::BAD_REQUEST
+            );
+        });
+        assert_matches!(parse_request(&put), Err(err @ RequestRejection::MethodNotAllowed(_)) => {
+            assert_eq!(
+                err.status_code(ResponseMediaType::GraphqlResponseJson),
+                StatusCode::METHOD_NOT_ALLOWED
+            );
+        });
+    }
+
+    #[test]
+    fn parse_persisted_query_requests() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(16);
+
+        // The SHA-256 hash of `{__typename}`
+        let hash = "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38";
+        let extensions = serde_json::json!({
+            "persistedQuery": { "version": 1, "sha256Hash": hash }
+        });
+        let hash_only = post(serde_json::json!({ "extensions": extensions }));
+        let with_query = post(serde_json::json!({
+            "query": "{__typename}",
+            "extensions": extensions,
+        }));
+
+        //* When
+        let unknown = parse_request_with_persisted_queries(&hash_only, &store);
+        let registered = parse_request_with_persisted_queries(&with_query, &store);
+        let resolved = parse_request_with_persisted_queries(&hash_only, &store);
+
+        //* Then
+        assert_matches!(unknown, Err(err @ RequestRejection::PersistedQueryNotFound) => {
+            assert_eq!(
+                err.status_code(ResponseMediaType::GraphqlResponseJson),
+                StatusCode::BAD_REQUEST
+            );
+            assert_eq!(err.status_code(ResponseMediaType::Json), StatusCode::OK);
+            assert_eq!(err.to_error().code(), Some(ErrorCode::PersistedQueryNotFound));
+        });
+        assert_matches!(registered, Ok(request) => {
+            assert_eq!(request.parameters.query.as_str(), "{__typename}");
+        });
+        assert_eq!(
+            store.get(hash).as_ref().map(|q| q.as_str()),
+            Some("{__typename}")
+        );
+        assert_matches!(resolved, Ok(request) => {
+            assert_eq!(request.parameters.query.as_str(), "{__typename}");
+            assert!(request.parameters.extensions.contains_key("persistedQuery"));
+        });
+    }
+
+    #[test]
+    fn resolve_persisted_queries_with_uppercase_hashes() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(16);
+
+        // The SHA-256 hash of `{__typename}`
+        let hash = "ecf4edb46db40b5132295c0291d62fb65d6759a9eedfa4d5d612dd5ec54a6b38";
+        let with_query = post(serde_json::json!({
+            "query": "{__typename}",
+            "extensions": {
+                "persistedQuery": { "version": 1, "sha256Hash": hash.to_ascii_uppercase() }
+            },
+        }));
+        let hash_only = post(serde_json::json!({
+            "extensions": { "persistedQuery": { "version": 1, "sha256Hash": hash } }
+        }));
+
+        //* When
+        let registered = parse_request_with_persisted_queries(&with_query, &store);
+        let resolved = parse_request_with_persisted_queries(&hash_only, &store);
+
+        //* Then
+        assert_matches!(registered, Ok(_));
+        assert_eq!(
+            store.get(hash).as_ref().map(|q| q.as_str()),
+            Some("{__typename}")
+        );
+        assert_matches!(resolved, Ok(request) => {
+            assert_eq!(request.parameters.query.as_str(), "{__typename}");
+        });
+    }
+
+    #[test]
+    fn fail_parse_invalid_persisted_query_requests() {
+        //* Given
+        let store = InMemoryPersistedQueryStore::new(16);
+
+        let hash_only = post(serde_json::json!({
+            "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "abcd" } }
+        }));
+        let mismatch = post(serde_json::json!({
+            "query": "{ a }",
+            "extensions": { "persistedQuery": { "version": 1, "sha256Hash": "abcd" } }
+        }));
+        let unsupported_version = post(serde_json::json!({
+            "query": "{ a }",
+            "extensions": { "persistedQuery": { "version": 2, "sha256Hash": "abcd" } }
+        }));
+        let invalid = post(serde_json::json!({
+            "query": "{ a }",
+            "extensions": { "persistedQuery": "abcd" }
+        }));
+
+        //* Then
+        assert_matches!(
+            parse_request(&hash_only),
+            Err(err @ RequestRejection::PersistedQueryNotSupported) => {
+                assert_eq!(
+                    err.status_code(ResponseMediaType::GraphqlResponseJson),
+                    StatusCode::BAD_REQUEST
+                );
+                assert_eq!(err.status_code(ResponseMediaType::Json), StatusCode::OK);
+                assert_eq!(err.to_error().code(), Some(ErrorCode::PersistedQueryNotSupported));
+            }
+        );
+        assert_matches!(
+            parse_request_with_persisted_queries(&mismatch, &store),
+            Err(RequestRejection::PersistedQueryHashMismatch)
+        );
+        assert_matches!(
+            parse_request_with_persisted_queries(&unsupported_version, &store),
+            Err(RequestRejection::InvalidPersistedQuery(_))
+        );
+        assert_matches!(
+            parse_request_with_persisted_queries(&invalid, &store),
+            Err(RequestRejection::InvalidPersistedQuery(_))
+        );
+        assert!(store.is_empty());
+
+        // Without a store, requests with the GraphQL document are not affected by the extension
+        assert_matches!(parse_request(&mismatch), Ok(_));
+    }
+}
@@ -0,0 +1,104 @@
+//! A local HTTP server stand-in for the integration tests.
+
+use reqwest::Url;
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::TcpListener,
+    task::JoinHandle,
+};
+
+/// A response of the local HTTP server stand-in: the status, headers and body.
+pub type Reply<'a> = (u16, &'a [(&'a str, &'a str)], &'a str);
+
+/// Start a local HTTP server stand-in replying to a single request with the given status, headers
+/// and body.
+///
+/// Returns the server URL and a handle resolving to the raw request received.
+pub async fn serve_once(
+    status: u16,
+    headers: &[(&str, &str)],
+    body: &str,
+) -> (Url, JoinHandle<String>) {
+    let (url, requests) = serve(&[(status, headers, body)]).await;
+    let request = tokio::spawn(async move {
+        let mut requests = requests.await.expect("Stand-in server failed");
+        requests.remove(0)
+    });
+    (url, request)
+}
+
+/// Start a local HTTP server stand-in replying to sequential requests, one per connection, with
+/// the given replies in order.
+///
+/// Returns the server URL and a handle resolving to the raw requests received.
+pub async fn serve(replies: &[Reply<'_>]) -> (Url, JoinHandle<Vec<String>>) {
+    let listener = TcpListener::bind("127.0.0.1:0")
+        .await
+        .expect("Failed to bind listener");
+    let addr = listener.local_addr().expect("Failed to get local address");
+
+    let responses = replies
+        .iter()
+        .map(|(status, headers, body)| {
+            let headers = headers
+                .iter()
+                .map(|(name, value)| format!("{name}: {value}\r\n"))
+                .collect::<String>();
+            format!(
+                "HTTP/1.1 {status} Status\r\n{headers}content-length: {}\r\nconnection: close\r\n\r\n{body}",
+                body.len()
+            )
+        })
+        .collect::<Vec<_>>();
+    let requests = tokio::spawn(async move {
+        let mut requests = Vec::with_capacity(responses.len());
+        for response in responses {
+            let (mut stream, _) = listener.accept().await.expect("Failed to accept");
+
+            // Read the request head and body before replying
+            let mut request = Vec::new();
+            let mut buf = [0u8; 1024];
+            loop {
+                let n = stream.read(&mut buf).await.expect("Failed to read request");
+                request.extend_from_slice(&buf[..n]);
+                if n == 0 || is_complete_request(&request) {
+                    break;
+                }
+            }
+
+            stream
+                .write_all(response.as_bytes())
+                .await
+                .expect("Failed to write response");
+            stream.shutdown().await.ok();
+
+            requests.push(String::from_utf8_lossy(&request).into_owned());
+        }
+        requests
+    });
+
+    (format!("http://{addr}/graphql").parse().unwrap(), requests)
+}
+
+/// Get the JSON body of the given raw request.
+#[allow(dead_code)] // Not every test inspects the request body
+pub fn request_body(request: &str) -> serde_json::Value {
+    let (_, body) = request
+        .split_once("\r\n\r\n")
+        .expect("request to have a body");
+    serde_json::from_str(body).expect("request body to be valid JSON")
+}
+
+/// Check if the buffered request contains the whole head and `content-length` body bytes.
+fn is_complete_request(request: &[u8]) -> bool {
+    let Some(head_end) = request.windows(4).position(|w| w == b"\r\n\r\n") else {
+        return false;
+    };
+    let head = String::from_utf8_lossy(&request[..head_end]).to_ascii_lowercase();
+    let content_length = head
+        .lines()
+        .find_map(|line| line.strip_prefix("content-length:"))
+        .and_then(|len| len.trim().parse::<usize>().ok())
+        .unwrap_or(0);
+    request.len() >= head_end + 4 + content_length
+}
@@ -0,0 +1,167 @@
+//! Integration tests for the `reqwest` HTTP client based client batched requests, using a local
+//! HTTP server stand-in.
+#![cfg(feature = "reqwest")]
+
+use assert_matches::assert_matches;
+use thegraph_graphql_http::http_client::{RequestError, ReqwestExt, ResponseError};
+
+mod common;
+
+/// The response data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    field: String,
+}
+
+#[tokio::test]
+async fn send_batch_and_map_responses_to_requests() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, request) = common::serve_once(
+        200,
+        &[("content-type", "application/json")],
+        r#"[
+            {"data":{"field":"first"}},
+            {"errors":[{"message":"Field 'field' not found"}]},
+            {"data":{"field":"third"}}
+        ]"#,
+    )
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_batch::<QueryResponse, _>(vec!["{ field }", "{ missing }", "{ field }"])
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(results) => {
+        assert_eq!(results.len(), 3);
+        assert_matches!(&results[0], Ok(Ok(QueryResponse { field })) => {
+            assert_eq!(field, "first");
+        });
+        assert_matches!(&results[1], Ok(Err(ResponseError::Failure { errors })) => {
+            assert_eq!(errors[0].message, "Field 'field' not found");
+        });
+        assert_matches!(&results[2], Ok(Ok(QueryResponse { field })) => {
+            assert_eq!(field, "third");
+        });
+    });
+
+    let request = request.await.expect("Stand-in server failed");
+    assert_eq!(
+        common::request_body(&request),
+        serde_json::json!([
+            { "query": "{ field }" },
+            { "query": "{ missing }" },
+            { "query": "{ field }" },
+        ])
+    );
+}
+
+#[tokio::test]
+async fn fail_only_the_malformed_batch_response() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, _request) = common::serve_once(
+        200,
+        &[("content-type", "application/json")],
+        r#"[
+            {"data":{"field":"first"}},
+            {"data":{"field":42}},
+            {"data":{"field":"third"}}
+        ]"#,
+    )
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_batch::<QueryResponse, _>(vec!["{ field }", "{ field }", "{ field }"])
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(results) => {
+        assert_eq!(results.len(), 3);
+        assert_matches!(&results[0], Ok(Ok(QueryResponse { field })) => {
+            assert_eq!(field, "first");
+        });
+        assert_matches!(
+            &results[1],
+            Err(RequestError::ResponseDeserializationError { response, .. }) => {
+                assert_eq!(response, r#"{"data":{"field":42}}"#);
+            }
+        );
+        assert_matches!(&results[2], Ok(Ok(QueryResponse { field })) => {
+            assert_eq!(field, "third");
+        });
+    });
+}
+
+#[tokio::test]
+async fn fail_when_server_rejects_batching() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, _request) = common::serve_once(
+        400,
+        &[("content-type", "application/graphql-response+json")],
+        r#"{"errors":[{"message":"Batched requests are not supported"}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_batch::<QueryResponse, _>(vec!["{ field }", "{ field }"])
+        .await;
+
+    //* Then
+    assert_matches!(response, Err(RequestError::BatchNotSupported(status, body)) => {
+        assert_eq!(status, reqwest::StatusCode::BAD_REQUEST);
+        assert!(body.contains("Batched requests are not supported"));
+    });
+}
+
+#[tokio::test]
+async fn fail_when_batch_response_length_mismatches() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, _request) = common::serve_once(
+        200,
+        &[("content-type", "application/json")],
+        r#"[{"data":{"field":"first"}}]"#,
+    )
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_batch::<QueryResponse, _>(vec!["{ field }", "{ field }"])
+        .await;
+
+    //* Then
+    assert_matches!(
+        response,
+        Err(RequestError::BatchResponseMismatch {
+            expected: 2,
+            received: 1
+        })
+    );
+}
+
+#[tokio::test]
+async fn send_empty_batch() {
+    //* Given
+    let client = reqwest::Client::new();
+
+    //* When
+    let response = client
+        .post("http://localhost:1/graphql")
+        .send_graphql_batch::<QueryResponse, &str>(vec![])
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(results) => {
+        assert!(results.is_empty());
+    });
+}
@@ -0,0 +1,173 @@
+//! Integration tests for the `reqwest` HTTP client based client `GET` requests, using a local HTTP
+//! server stand-in.
+#![cfg(feature = "reqwest")]
+
+use assert_matches::assert_matches;
+use thegraph_graphql_http::{
+    http::request::RequestParameters,
+    http_client::{GetRequestOptions, ReqwestExt},
+};
+
+mod common;
+
+/// The response data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    field: String,
+}
+
+/// Start a local HTTP server stand-in replying to a single request with a successful GraphQL
+/// response.
+async fn serve_once() -> (reqwest::Url, tokio::task::JoinHandle<String>) {
+    common::serve_once(
+        200,
+        &[("content-type", "application/graphql-response+json")],
+        r#"{"data":{"field":"value"}}"#,
+    )
+    .await
+}
+
+#[tokio::test]
+async fn send_query_using_get_method() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, request) = serve_once().await;
+
+    let req = RequestParameters {
+        query: "query Field($id: ID) { field(id: $id) }".to_string().into(),
+        operation_name: Some("Field".to_string()),
+        variables: serde_json::json!({"id": "1"}).as_object().unwrap().clone(),
+        extensions: Default::default(),
+    };
+
+    //* When
+    let response = client
+        .get(url)
+        .send_graphql_get::<QueryResponse>(req, GetRequestOptions::default())
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let request = request.await.expect("Stand-in server failed");
+    let request_line = request.lines().next().unwrap();
+    assert_eq!(
+        request_line,
+        "GET /graphql?query=query+Field%28%24id%3A+ID%29+%7B+field%28id%3A+%24id%29+%7D\
+            &operationName=Field&variables=%7B%22id%22%3A%221%22%7D HTTP/1.1"
+    );
+
+    let request = request.to_ascii_lowercase();
+    assert!(request.contains("accept: application/graphql-response+json"));
+    assert!(!request.contains("content-type:"));
+}
+
+#[tokio::test]
+async fn get_request_keeps_url_query_parameters() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (mut url, request) = serve_once().await;
+    url.set_query(Some("api=1"));
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_get::<QueryResponse>("{ field }", GetRequestOptions::default())
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(_)));
+
+    let request = request.await.expect("Stand-in server failed");
+    assert!(request.starts_with("GET /graphql?api=1&query=%7B+field+%7D HTTP/1.1"));
+}
+
+#[tokio::test]
+async fn long_get_request_falls_back_to_post_method() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, request) = serve_once().await;
+
+    let options = GetRequestOptions { max_url_length: 32 };
+
+    //* When
+    let response = client
+        .get(url)
+        .send_graphql_get::<QueryResponse>("{ field }", options)
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(_)));
+
+    let request = request.await.expect("Stand-in server failed");
+    assert!(request.starts_with("POST /graphql HTTP/1.1"));
+    assert!(request.ends_with(r#"{"query":"{ field }"}"#));
+}
+
+#[cfg(feature = "graphql-parser")]
+mod graphql_parser {
+    use assert_matches::assert_matches;
+    use thegraph_graphql_http::{
+        http::request::RequestParameters,
+        http_client::{GetRequestOptions, RequestError, ReqwestExt},
+    };
+
+    use super::{QueryResponse, serve_once};
+
+    #[test]
+    fn get_request_rejects_mutations() {
+        //* Given
+        let client = reqwest::Client::new();
+
+        //* When
+        let result = client
+            .get("http://localhost/graphql")
+            .graphql_get("mutation { update(id: 1) }", GetRequestOptions::default());
+
+        //* Then
+        assert_matches!(
+            result,
+            Err(RequestError::OperationNotAllowedOverGet("mutation"))
+        );
+    }
+
+    #[tokio::test]
+    async fn get_request_selects_operation_by_name() {
+        //* Given
+        let client = reqwest::Client::new();
+        let (url, request) = serve_once().await;
+
+        let document = "query Read { field } mutation Write { update(id: 1) }";
+        let read = RequestParameters {
+            query: document.to_string().into(),
+            operation_name: Some("Read".to_string()),
+            variables: Default::default(),
+            extensions: Default::default(),
+        };
+        let write = RequestParameters {
+            operation_name: Some("Write".to_string()),
+            ..read.clone()
+        };
+
+        //* When
+        let write = client
+            .get(url.clone())
+            .graphql_get(write, GetRequestOptions::default());
+        let read = client
+            .get(url)
+            .send_graphql_get::<QueryResponse>(read, GetRequestOptions::default())
+            .await;
+
+        //* Then
+        assert_matches!(
+            write,
+            Err(RequestError::OperationNotAllowedOverGet("mutation"))
+        );
+        assert_matches!(read, Ok(Ok(_)));
+
+        let request = request.await.expect("Stand-in server failed");
+        assert!(request.starts_with("GET /graphql?"));
+    }
+}
@@ -0,0 +1,198 @@
+//! Integration tests for the `reqwest` HTTP client based client incremental delivery requests,
+//! using a local HTTP server stand-in.
+#![cfg(feature = "reqwest")]
+
+use assert_matches::assert_matches;
+use futures_util::StreamExt as _;
+use indoc::indoc;
+use thegraph_graphql_http::{
+    http::incremental::{IncrementalPayload, merge_payloads},
+    http_client::{RequestError, ReqwestExt},
+};
+
+mod common;
+
+/// The initial payload data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    indexer: Indexer,
+}
+
+#[derive(Debug, serde::Deserialize)]
+struct Indexer {
+    id: String,
+    #[serde(default)]
+    allocations: Vec<String>,
+}
+
+/// A canned incremental delivery response body, with a deferred fragment and streamed list items.
+const MULTIPART_BODY: &str = indoc! {r#"
+
+    --graphql
+    content-type: application/json; charset=utf-8
+
+    {"data":{"indexer":{"id":"0x01","allocations":["0xa"]}},"hasNext":true}
+    --graphql
+    content-type: application/json; charset=utf-8
+
+    {}
+    --graphql
+    content-type: application/json; charset=utf-8
+
+    {"incremental":[{"data":{"url":"https://indexer.example"},"path":["indexer"],"label":"details"}],"hasNext":true}
+    --graphql
+    content-type: application/json; charset=utf-8
+
+    {"incremental":[{"items":["0xb","0xc"],"path":["indexer","allocations",1]}],"hasNext":false}
+    --graphql--
+"#};
+
+/// Convert the line breaks of the given body to CRLF, as required by the multipart media type.
+fn crlf(body: &str) -> String {
+    body.replace('\n', "\r\n")
+}
+
+#[tokio::test]
+async fn receive_incremental_payloads() {
+    //* Given
+    let client = reqwest::Client::new();
+    let body = crlf(MULTIPART_BODY);
+    let (url, request) = common::serve_once(
+        200,
+        &[("content-type", "multipart/mixed; boundary=\"graphql\"")],
+        &body,
+    )
+    .await;
+
+    //* When
+    let stream = client
+        .post(url)
+        .send_graphql_incremental::<QueryResponse>(
+            "{ indexer { id ... @defer(label: \"details\") { url } allocations @stream(initialCount: 1) } }",
+        )
+        .await;
+
+    //* Then
+    let payloads = match stream {
+        Ok(stream) => stream.collect::<Vec<_>>().await,
+        Err(err) => panic!("request failed: {err}"),
+    };
+    assert_eq!(payloads.len(), 3);
+    assert_matches!(&payloads[0], Ok(IncrementalPayload::Initial(initial)) => {
+        let data = initial.data.as_ref().expect("initial data to be present");
+        assert_eq!(data.indexer.id, "0x01");
+        assert_eq!(data.indexer.allocations, ["0xa"]);
+        assert!(initial.has_next);
+    });
+    assert_matches!(&payloads[1], Ok(IncrementalPayload::Subsequent(subsequent)) => {
+        assert_eq!(subsequent.incremental[0].label.as_deref(), Some("details"));
+        assert!(subsequent.has_next);
+    });
+    assert_matches!(&payloads[2], Ok(IncrementalPayload::Subsequent(subsequent)) => {
+        assert!(!subsequent.has_next);
+    });
+
+    let request = request.await.expect("Stand-in server failed");
+    assert!(request.to_ascii_lowercase().contains(
+        "accept: multipart/mixed; deferspec=20220824, application/graphql-response+json"
+    ));
+}
+
+#[tokio::test]
+async fn merge_incremental_payloads() {
+    //* Given
+    let client = reqwest::Client::new();
+    let body = crlf(MULTIPART_BODY);
+    let (url, _request) = common::serve_once(
+        200,
+        &[("content-type", "multipart/mixed; boundary=graphql")],
+        &body,
+    )
+    .await;
+
+    //* When
+    let payloads = client
+        .post(url)
+        .send_graphql_incremental::<serde_json::Value>("{ indexer { id } }")
+        .await
+        .expect("request to succeed")
+        .map(|payload| payload.expect("payload to be valid"))
+        .collect::<Vec<_>>()
+        .await;
+    let response = merge_payloads(payloads);
+
+    //* Then
+    assert_matches!(response, Ok(response) => {
+        assert_eq!(
+            response.data,
+            Some(serde_json::json!({
+                "indexer": {
+                    "id": "0x01",
+                    "url": "https://indexer.example",
+                    "allocations": ["0xa", "0xb", "0xc"]
+                }
+            }))
+        );
+        assert!(response.errors.is_empty());
+    });
+}
+
+#[tokio::test]
+async fn receive_regular_response_as_initial_payload() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, _request) = common::serve_once(
+        200,
+        &[("content-type", "application/graphql-response+json")],
+        r#"{"data":{"indexer":{"id":"0x01"}}}"#,
+    )
+    .await;
+
+    //* When
+    let stream = client
+        .post(url)
+        .send_graphql_incremental::<QueryResponse>("{ indexer { id } }")
+        .await;
+
+    //* Then
+    let payloads = match stream {
+        Ok(stream) => stream.collect::<Vec<_>>().await,
+        Err(err) => panic!("request failed: {err}"),
+    };
+    assert_matches!(&payloads[..], [Ok(IncrementalPayload::Initial(initial))] => {
+        assert_eq!(initial.data.as_ref().map(|data| data.indexer.id.as_str()), Some("0x01"));
+        assert!(!initial.has_next);
+    });
+}
+
+#[tokio::test]
+async fn fail_on_truncated_multipart_response() {
+    //* Given
+    let client = reqwest::Client::new();
+    let body = crlf(indoc! {r#"
+        ---
+        content-type: application/json; charset=utf-8
+
+        {"data":{"indexer":{"id":"0x01"}},"hasNext":true}
+        ---
+    "#});
+    let (url, _request) =
+        common::serve_once(200, &[("content-type", "multipart/mixed")], &body).await;
+
+    //* When
+    let stream = client
+        .post(url)
+        .send_graphql_incremental::<QueryResponse>("{ indexer { id } }")
+        .await;
+
+    //* Then
+    let payloads = match stream {
+        Ok(stream) => stream.collect::<Vec<_>>().await,
+        Err(err) => panic!("request failed: {err}"),
+    };
+    assert_eq!(payloads.len(), 2);
+    assert_matches!(&payloads[0], Ok(IncrementalPayload::Initial(_)));
+    assert_matches!(&payloads[1], Err(RequestError::ResponseRecvError(_, message)) => {
+        assert_eq!(message, "Unexpected end of multipart response body");
+    });
+}
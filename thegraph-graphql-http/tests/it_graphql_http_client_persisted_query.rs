@@ -0,0 +1,152 @@
+//! Integration tests for the `reqwest` HTTP client based client automatic persisted queries, using
+//! a local HTTP server stand-in.
+#![cfg(feature = "reqwest")]
+
+use assert_matches::assert_matches;
+use thegraph_graphql_http::http_client::{ReqwestExt, ResponseError};
+
+mod common;
+
+/// The response data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    field: String,
+}
+
+/// The SHA-256 hash of the test GraphQL document, `{ field }`.
+const QUERY_HASH: &str = "06626312c84a7d495de5d55ab48a6baf9482dd9a943c0a0a738fd04c658b3654";
+
+/// The successful GraphQL response.
+const DATA_RESPONSE: common::Reply<'static> = (
+    200,
+    &[("content-type", "application/graphql-response+json")],
+    r#"{"data":{"field":"value"}}"#,
+);
+
+#[tokio::test]
+async fn send_hash_only_when_query_is_known() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, request) =
+        common::serve_once(DATA_RESPONSE.0, DATA_RESPONSE.1, DATA_RESPONSE.2).await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let request = request.await.expect("Stand-in server failed");
+    assert_eq!(
+        common::request_body(&request),
+        serde_json::json!({
+            "extensions": {
+                "persistedQuery": { "version": 1, "sha256Hash": QUERY_HASH }
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn retry_with_query_when_hash_is_not_found() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, requests) = common::serve(&[
+        (
+            200,
+            &[("content-type", "application/json")],
+            r#"{"errors":[{"message":"PersistedQueryNotFound","extensions":{"code":"PERSISTED_QUERY_NOT_FOUND"}}]}"#,
+        ),
+        DATA_RESPONSE,
+    ])
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let requests = requests.await.expect("Stand-in server failed");
+    assert_eq!(requests.len(), 2);
+    assert!(common::request_body(&requests[0]).get("query").is_none());
+    assert_eq!(
+        common::request_body(&requests[1]),
+        serde_json::json!({
+            "query": "{ field }",
+            "extensions": {
+                "persistedQuery": { "version": 1, "sha256Hash": QUERY_HASH }
+            }
+        })
+    );
+}
+
+#[tokio::test]
+async fn retry_without_hash_when_not_supported() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, requests) = common::serve(&[
+        (
+            400,
+            &[("content-type", "application/graphql-response+json")],
+            r#"{"errors":[{"message":"PersistedQueryNotSupported"}]}"#,
+        ),
+        DATA_RESPONSE,
+    ])
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let requests = requests.await.expect("Stand-in server failed");
+    assert_eq!(requests.len(), 2);
+    assert_eq!(
+        common::request_body(&requests[1]),
+        serde_json::json!({ "query": "{ field }" })
+    );
+}
+
+#[tokio::test]
+async fn do_not_retry_on_other_errors() {
+    //* Given
+    let client = reqwest::Client::new();
+    let (url, requests) = common::serve(&[(
+        200,
+        &[("content-type", "application/json")],
+        r#"{"errors":[{"message":"Unauthorized","extensions":{"code":"UNAUTHORIZED"}}]}"#,
+    )])
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Unauthorized");
+    });
+
+    let requests = requests.await.expect("Stand-in server failed");
+    assert_eq!(requests.len(), 1);
+}
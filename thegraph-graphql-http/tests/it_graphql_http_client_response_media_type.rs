@@ -0,0 +1,336 @@
+//! Integration tests for the `reqwest` HTTP client based client response processing, using a
+//! local HTTP server stand-in.
+#![cfg(feature = "reqwest")]
+
+use assert_matches::assert_matches;
+use reqwest::{StatusCode, Url};
+use thegraph_graphql_http::{
+    http::response::{ErrorCode, PathSegment},
+    http_client::{PartialResponse, RequestError, ReqwestExt, ResponseError},
+};
+
+mod common;
+
+/// The `application/graphql-response+json` media type.
+const GRAPHQL_RESPONSE: &str = "application/graphql-response+json";
+
+/// A GraphQL query, its contents are irrelevant to the stand-in server.
+const QUERY: &str = "{ field }";
+
+/// The response data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    field: String,
+}
+
+/// Start a local HTTP server stand-in replying to a single request with the given status,
+/// `Content-Type` header and body.
+async fn serve_once(status: u16, content_type: &str, body: &str) -> Url {
+    serve_once_with_headers(status, &[("content-type", content_type)], body).await
+}
+
+/// Start a local HTTP server stand-in replying to a single request with the given status, headers
+/// and body.
+async fn serve_once_with_headers(status: u16, headers: &[(&str, &str)], body: &str) -> Url {
+    let (url, _) = common::serve_once(status, headers, body).await;
+    url
+}
+
+#[tokio::test]
+async fn success_response_with_data() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        "application/graphql-response+json; charset=utf-8",
+        r#"{"data":{"field":"value"}}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+}
+
+#[tokio::test]
+async fn client_error_response_with_request_errors() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        400,
+        GRAPHQL_RESPONSE,
+        r#"{"errors":[{"message":"Syntax Error: Unexpected <EOF>."}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Syntax Error: Unexpected <EOF>.");
+    });
+}
+
+#[tokio::test]
+async fn server_error_response_with_request_errors() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        500,
+        GRAPHQL_RESPONSE,
+        r#"{"errors":[{"message":"Internal server error"}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors[0].message, "Internal server error");
+    });
+}
+
+#[tokio::test]
+async fn server_error_response_with_non_graphql_body() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(502, GRAPHQL_RESPONSE, "<html>Bad Gateway</html>").await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Err(RequestError::ResponseRecvError(status, body)) => {
+        assert_eq!(status, StatusCode::BAD_GATEWAY);
+        assert_eq!(body, "<html>Bad Gateway</html>");
+    });
+}
+
+#[tokio::test]
+async fn client_error_response_with_data_is_rejected() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(400, GRAPHQL_RESPONSE, r#"{"data":{"field":"value"}}"#).await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Err(RequestError::ResponseRecvError(status, _)) => {
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+    });
+}
+
+#[tokio::test]
+async fn success_response_with_partial_data_is_a_failure() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        GRAPHQL_RESPONSE,
+        r#"{"data":{"field":"value"},"errors":[{"message":"Field error","path":["field"]}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors[0].message, "Field error");
+    });
+}
+
+#[tokio::test]
+async fn success_response_with_partial_data_is_kept() {
+    //* Given
+    #[derive(Debug, serde::Deserialize)]
+    struct PartialQueryResponse {
+        field: Option<String>,
+        other: String,
+    }
+
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        GRAPHQL_RESPONSE,
+        r#"{"data":{"field":null,"other":"value"},"errors":[{"message":"Field error","path":["field"]}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client
+        .post(url)
+        .send_graphql_partial::<PartialQueryResponse>(QUERY)
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(PartialResponse { data, errors })) => {
+        assert_eq!(data.field, None);
+        assert_eq!(data.other, "value");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors[0].message, "Field error");
+    });
+}
+
+#[tokio::test]
+async fn error_with_list_index_path_and_extensions() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        GRAPHQL_RESPONSE,
+        r#"{"data":null,"errors":[{"message":"Field error","path":["items",1,"field"],"extensions":{"code":"BLOCK_NOT_FOUND"}}]}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors[0].path[1], PathSegment::Index(1));
+        assert_eq!(errors[0].code(), Some(ErrorCode::BlockNotFound));
+    });
+}
+
+#[tokio::test]
+async fn content_type_with_parameters_is_matched() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        503,
+        r#"Application/GraphQL-Response+JSON ; charset="UTF-8""#,
+        "Service Unavailable",
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    // A legacy response would fail to deserialize the body instead
+    assert_matches!(response, Err(RequestError::ResponseRecvError(status, _)) => {
+        assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    });
+}
+
+#[tokio::test]
+async fn unsupported_charset_is_rejected() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        "application/graphql-response+json; charset=iso-8859-1",
+        r#"{"data":{"field":"value"}}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Err(RequestError::ResponseRecvError(_, message)) => {
+        assert!(message.contains("iso-8859-1"));
+    });
+}
+
+#[tokio::test]
+async fn legacy_response_with_parameters() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        200,
+        "application/json; charset=utf-8",
+        r#"{"data":{"field":"value"}}"#,
+    )
+    .await;
+
+    //* When
+    let response = client.post(url).send_graphql::<QueryResponse>(QUERY).await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+}
+
+#[tokio::test]
+async fn response_envelope_retains_metadata_and_raw_body() {
+    //* Given
+    #[derive(Debug, serde::Deserialize)]
+    struct BorrowedQueryResponse<'a> {
+        field: &'a str,
+    }
+
+    let body = r#"{"data":{"field":"value"},"extensions":{"cost":42}}"#;
+
+    let client = reqwest::Client::new();
+    let url = serve_once_with_headers(
+        200,
+        &[
+            ("content-type", GRAPHQL_RESPONSE),
+            ("graph-indexed", r#"{"block":{"number":1}}"#),
+        ],
+        body,
+    )
+    .await;
+
+    //* When
+    let envelope = client
+        .post(url)
+        .send_graphql_envelope(QUERY)
+        .await
+        .expect("Request failed");
+
+    //* Then
+    assert_eq!(envelope.status, StatusCode::OK);
+    assert_eq!(
+        envelope.headers["graph-indexed"],
+        r#"{"block":{"number":1}}"#
+    );
+    assert_eq!(envelope.body_str(), Ok(body));
+
+    assert_matches!(envelope.decode::<BorrowedQueryResponse>(), Ok(decoded) => {
+        assert_eq!(decoded.extensions["cost"], 42);
+        assert_matches!(decoded.result, Ok(PartialResponse { data, errors }) => {
+            assert_eq!(data.field, "value");
+            assert!(errors.is_empty());
+        });
+    });
+}
+
+#[tokio::test]
+async fn response_envelope_decodes_request_errors() {
+    //* Given
+    let client = reqwest::Client::new();
+    let url = serve_once(
+        400,
+        GRAPHQL_RESPONSE,
+        r#"{"errors":[{"message":"Request error"}],"extensions":{"trace":"abc"}}"#,
+    )
+    .await;
+
+    //* When
+    let envelope = client
+        .post(url)
+        .send_graphql_envelope(QUERY)
+        .await
+        .expect("Request failed");
+
+    //* Then
+    assert_eq!(envelope.status, StatusCode::BAD_REQUEST);
+    assert_matches!(envelope.decode::<QueryResponse>(), Ok(decoded) => {
+        assert_eq!(decoded.extensions["trace"], "abc");
+        assert_matches!(decoded.result, Err(ResponseError::Failure { errors }) => {
+            assert_eq!(errors[0].message, "Request error");
+        });
+    });
+}
@@ -0,0 +1,320 @@
+//! Integration tests for the transport-independent client, using in-process `tower` services.
+#![cfg(feature = "tower")]
+
+use std::{
+    convert::Infallible,
+    sync::{Arc, Mutex},
+};
+
+use assert_matches::assert_matches;
+use bytes::Bytes;
+use http_body_util::{BodyExt as _, Full};
+use thegraph_graphql_http::http_client::{
+    GetRequestOptions, GraphQlClient, GraphQlTransport, RequestError, ResponseError,
+    ServiceTransport,
+};
+
+#[cfg(feature = "reqwest")]
+mod common;
+
+/// The response data type.
+#[derive(Debug, serde::Deserialize)]
+struct QueryResponse {
+    field: String,
+}
+
+/// Create a client backed by an in-process service replying to every request with the given
+/// status, content type and body.
+///
+/// Returns the client and the requests received by the service.
+fn client(
+    status: u16,
+    content_type: &'static str,
+    body: &'static str,
+) -> (
+    GraphQlClient<impl GraphQlTransport>,
+    Arc<Mutex<Vec<http::Request<Bytes>>>>,
+) {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let service = tower::service_fn({
+        let requests = requests.clone();
+        move |req: http::Request<Bytes>| {
+            requests.lock().unwrap().push(req);
+            async move {
+                let response = http::Response::builder()
+                    .status(status)
+                    .header(http::header::CONTENT_TYPE, content_type)
+                    .body(Full::new(Bytes::from_static(body.as_bytes())))
+                    .unwrap();
+                Ok::<_, Infallible>(response)
+            }
+        }
+    });
+
+    let client = GraphQlClient::new(
+        ServiceTransport::new(service),
+        "http://localhost/graphql".parse().unwrap(),
+    );
+    (client, requests)
+}
+
+#[tokio::test]
+async fn send_query_in_process() {
+    //* Given
+    let (client, requests) = client(
+        200,
+        "application/graphql-response+json",
+        r#"{"data":{"field":"value"}}"#,
+    );
+
+    //* When
+    let response = client.send_graphql::<QueryResponse>("{ field }").await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let request = &requests[0];
+    assert_eq!(request.method(), http::Method::POST);
+    assert_eq!(request.uri(), "http://localhost/graphql");
+    assert_eq!(
+        request.headers()[http::header::CONTENT_TYPE],
+        "application/json"
+    );
+    assert_eq!(
+        request.headers()[http::header::ACCEPT],
+        "application/graphql-response+json; charset=utf-8, application/json; charset=utf-8"
+    );
+    assert_eq!(request.body().as_ref(), br#"{"query":"{ field }"}"#);
+}
+
+#[tokio::test]
+async fn process_request_error_in_process() {
+    //* Given
+    let (client, _requests) = client(
+        400,
+        "application/graphql-response+json",
+        r#"{"errors":[{"message":"Syntax error"}]}"#,
+    );
+
+    //* When
+    let response = client.send_graphql::<QueryResponse>("{ field").await;
+
+    //* Then
+    assert_matches!(response, Ok(Err(ResponseError::Failure { errors })) => {
+        assert_eq!(errors[0].message, "Syntax error");
+    });
+}
+
+#[tokio::test]
+async fn send_batch_in_process() {
+    //* Given
+    let (client, requests) = client(
+        200,
+        "application/json",
+        r#"[{"data":{"field":"first"}},{"data":{"field":"second"}}]"#,
+    );
+
+    //* When
+    let response = client
+        .send_graphql_batch::<QueryResponse, _>(["{ first: field }", "{ second: field }"])
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(results) => {
+        assert_matches!(&results[..], [Ok(Ok(first)), Ok(Ok(second))] => {
+            assert_eq!(first.field, "first");
+            assert_eq!(second.field, "second");
+        });
+    });
+    assert_eq!(
+        requests.lock().unwrap()[0].body().as_ref(),
+        br#"[{"query":"{ first: field }"},{"query":"{ second: field }"}]"#
+    );
+}
+
+#[tokio::test]
+async fn send_query_to_body_typed_service() {
+    //* Given
+    let bodies = Arc::new(Mutex::new(Vec::new()));
+    let service = tower::service_fn({
+        let bodies = bodies.clone();
+        move |req: http::Request<Full<Bytes>>| {
+            let bodies = bodies.clone();
+            async move {
+                // Receive the request body as any `http_body::Body`, e.g., a _hyper_ client would
+                let body = req.into_body().collect().await?.to_bytes();
+                bodies.lock().unwrap().push(body);
+
+                let response = http::Response::builder()
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        "application/graphql-response+json",
+                    )
+                    .body(Full::new(Bytes::from_static(
+                        br#"{"data":{"field":"value"}}"#,
+                    )))
+                    .unwrap();
+                Ok::<_, Infallible>(response)
+            }
+        }
+    });
+    let client = GraphQlClient::new(
+        ServiceTransport::new(service),
+        "http://localhost/graphql".parse().unwrap(),
+    );
+
+    //* When
+    let response = client.send_graphql::<QueryResponse>("{ field }").await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+    assert_eq!(
+        bodies.lock().unwrap()[0].as_ref(),
+        br#"{"query":"{ field }"}"#
+    );
+}
+
+#[tokio::test]
+async fn fail_on_service_error() {
+    //* Given
+    let service = tower::service_fn(|_req: http::Request<Bytes>| async {
+        Err::<http::Response<Full<Bytes>>, _>(std::io::Error::other("connection refused"))
+    });
+    let client = GraphQlClient::new(
+        ServiceTransport::new(service),
+        "http://localhost/graphql".parse().unwrap(),
+    );
+
+    //* When
+    let response = client.send_graphql::<QueryResponse>("{ field }").await;
+
+    //* Then
+    assert_matches!(response, Err(RequestError::TransportError(err)) => {
+        assert_eq!(err.to_string(), "connection refused");
+    });
+}
+
+#[cfg(feature = "reqwest")]
+#[tokio::test]
+async fn send_query_using_reqwest_transport() {
+    //* Given
+    let (url, _request) = common::serve_once(
+        200,
+        &[("content-type", "application/graphql-response+json")],
+        r#"{"data":{"field":"value"}}"#,
+    )
+    .await;
+
+    let client = GraphQlClient::new(reqwest::Client::new(), url.as_str().parse().unwrap());
+
+    //* When
+    let response = client.send_graphql::<QueryResponse>("{ field }").await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+}
+
+#[tokio::test]
+async fn send_query_using_get_method_in_process() {
+    //* Given
+    let (client, requests) = client(
+        200,
+        "application/graphql-response+json",
+        r#"{"data":{"field":"value"}}"#,
+    );
+
+    //* When
+    let response = client
+        .send_graphql_get::<QueryResponse>("{ field }", GetRequestOptions::default())
+        .await;
+
+    //* Then
+    assert_matches!(response, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "value");
+    });
+
+    let requests = requests.lock().unwrap();
+    let request = &requests[0];
+    assert_eq!(request.method(), http::Method::GET);
+    assert_eq!(
+        request.uri(),
+        "http://localhost/graphql?query=%7B+field+%7D"
+    );
+    assert!(!request.headers().contains_key(http::header::CONTENT_TYPE));
+    assert!(request.body().is_empty());
+}
+
+#[tokio::test]
+async fn stop_sending_hash_once_persisted_queries_are_not_supported() {
+    //* Given
+    let replies = Arc::new(Mutex::new(vec![
+        (
+            400,
+            r#"{"errors":[{"message":"PersistedQueryNotSupported"}]}"#,
+        ),
+        (200, r#"{"data":{"field":"first"}}"#),
+        (200, r#"{"data":{"field":"second"}}"#),
+    ]));
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let service = tower::service_fn({
+        let requests = requests.clone();
+        move |req: http::Request<Bytes>| {
+            requests.lock().unwrap().push(req);
+            let (status, body) = replies.lock().unwrap().remove(0);
+            async move {
+                let response = http::Response::builder()
+                    .status(status)
+                    .header(
+                        http::header::CONTENT_TYPE,
+                        "application/graphql-response+json",
+                    )
+                    .body(Full::new(Bytes::from_static(body.as_bytes())))
+                    .unwrap();
+                Ok::<_, Infallible>(response)
+            }
+        }
+    });
+    let client = GraphQlClient::new(
+        ServiceTransport::new(service),
+        "http://localhost/graphql".parse().unwrap(),
+    );
+
+    //* When
+    let first = client
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+    let second = client
+        .clone()
+        .send_graphql_persisted::<QueryResponse>("{ field }")
+        .await;
+
+    //* Then
+    assert_matches!(first, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "first");
+    });
+    assert_matches!(second, Ok(Ok(QueryResponse { field })) => {
+        assert_eq!(field, "second");
+    });
+
+    // The hash is sent only once, the client remembers the server does not support it
+    let bodies = requests
+        .lock()
+        .unwrap()
+        .iter()
+        .map(|req| req.body().clone())
+        .collect::<Vec<_>>();
+    assert_eq!(bodies.len(), 3);
+    let hash_only: serde_json::Value = serde_json::from_slice(&bodies[0]).unwrap();
+    assert!(hash_only.get("query").is_none());
+    assert!(hash_only["extensions"].get("persistedQuery").is_some());
+    assert_eq!(bodies[1].as_ref(), br#"{"query":"{ field }"}"#);
+    assert_eq!(bodies[2].as_ref(), br#"{"query":"{ field }"}"#);
+}
@@ -0,0 +1,45 @@
+//! Benchmarks of the `graph-attestation` header JSON and compact encodings.
+//!
+//! The header value size of each encoding is reported as the benchmarks throughput.
+
+use std::hint::black_box;
+
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+use fake::{Fake, Faker};
+use headers::{Header, HeaderValue};
+use thegraph_headers::graph_attestation::{Attestation, CompactGraphAttestation, GraphAttestation};
+
+/// Encode the typed header into a header value.
+fn encode<H: Header>(header: &H) -> HeaderValue {
+    let mut values = Vec::with_capacity(1);
+    header.encode(&mut values);
+    values.pop().expect("header to have been encoded")
+}
+
+fn bench_graph_attestation(c: &mut Criterion) {
+    let attestation = Faker.fake::<Attestation>();
+
+    let json = GraphAttestation(Some(attestation.clone()));
+    let compact = CompactGraphAttestation(Some(attestation));
+
+    let json_value = encode(&json);
+    let compact_value = encode(&compact);
+
+    let mut group = c.benchmark_group("graph_attestation");
+
+    group.throughput(Throughput::Bytes(json_value.len() as u64));
+    group.bench_function("encode/json", |b| b.iter(|| encode(black_box(&json))));
+    group.bench_function("decode/json", |b| {
+        b.iter(|| GraphAttestation::decode(&mut std::iter::once(black_box(&json_value))))
+    });
+
+    group.throughput(Throughput::Bytes(compact_value.len() as u64));
+    group.bench_function("encode/compact", |b| b.iter(|| encode(black_box(&compact))));
+    group.bench_function("decode/compact", |b| {
+        b.iter(|| GraphAttestation::decode(&mut std::iter::once(black_box(&compact_value))))
+    });
+    group.finish();
+}
+
+criterion_group!(benches, bench_graph_attestation);
+criterion_main!(benches);
@@ -0,0 +1,383 @@
+//! _axum_ extractors and responders for _The Graph_ typed headers.
+//!
+//! This module provides the following _axum_ integrations:
+//!
+//! - [`GraphHeader`]: An extractor for any _typed header_ that rejects the request if the header
+//!   is missing or invalid. The rejection type is configurable. Use `Option<GraphHeader<H>>` to
+//!   tolerate a missing header.
+//! - [`MaybeGraphHeader`]: An extractor that never rejects the request. It tolerates missing and
+//!   invalid headers, and exposes the reason why the header could not be extracted.
+//! - [`IntoResponseParts`] implementations for the [`GraphAttestable`], [`GraphIndexed`] and
+//!   `graph-attestation` typed headers, so they can be returned from handlers.
+//!
+//! # Example
+//!
+//! ```rust
+//! use axum::{Router, routing::get};
+//! use thegraph_headers::{
+//!     axum::{GraphHeader, MaybeGraphHeader},
+//!     graph_attestable::GraphAttestable,
+//!     graph_indexed::GraphIndexed,
+//! };
+//!
+//! async fn handler(
+//!     MaybeGraphHeader(indexed): MaybeGraphHeader<GraphIndexed>,
+//! ) -> (GraphAttestable, &'static str) {
+//!     match indexed {
+//!         Ok(GraphIndexed(info)) => (GraphAttestable(true), "indexed"),
+//!         Err(_) => (GraphAttestable(false), "unknown"),
+//!     }
+//! }
+//!
+//! let app: Router = Router::new().route("/", get(handler));
+//! ```
+
+use std::{convert::Infallible, marker::PhantomData, ops::Deref};
+
+use axum_core::{
+    extract::{FromRequestParts, OptionalFromRequestParts},
+    response::{IntoResponse, IntoResponseParts, Response, ResponseParts},
+};
+use headers::{Header, HeaderMapExt as _, HeaderName};
+use http::{StatusCode, request::Parts};
+
+#[cfg(feature = "attestation")]
+use crate::graph_attestation::{CompactGraphAttestation, GraphAttestation};
+use crate::{graph_attestable::GraphAttestable, graph_indexed::GraphIndexed};
+
+/// The rejection of the [`GraphHeader`] and [`MaybeGraphHeader`] extractors.
+///
+/// Responds with a `400 Bad Request` status code.
+#[derive(Debug, thiserror::Error)]
+pub enum GraphHeaderRejection {
+    /// The header is missing
+    #[error("missing `{name}` header")]
+    Missing {
+        /// The name of the header
+        name: &'static HeaderName,
+    },
+
+    /// The header is present but its value could not be decoded
+    #[error("invalid `{name}` header")]
+    Invalid {
+        /// The name of the header
+        name: &'static HeaderName,
+        /// The header decoding error
+        #[source]
+        source: headers::Error,
+    },
+}
+
+impl GraphHeaderRejection {
+    /// Get the name of the rejected header.
+    pub fn name(&self) -> &'static HeaderName {
+        match self {
+            Self::Missing { name } | Self::Invalid { name, .. } => name,
+        }
+    }
+}
+
+impl IntoResponse for GraphHeaderRejection {
+    fn into_response(self) -> Response {
+        (StatusCode::BAD_REQUEST, self.to_string()).into_response()
+    }
+}
+
+/// Decode the typed header from the request parts.
+fn decode<H: Header>(parts: &Parts) -> Result<Option<H>, GraphHeaderRejection> {
+    parts
+        .headers
+        .typed_try_get::<H>()
+        .map_err(|source| GraphHeaderRejection::Invalid {
+            name: H::name(),
+            source,
+        })
+}
+
+/// An extractor for _typed headers_.
+///
+/// Rejects the request if the header is missing or invalid. The rejection type, `R`, can be any
+/// type convertible from a [`GraphHeaderRejection`], e.g., a service's own error response type.
+///
+/// When extracted as `Option<GraphHeader<H, R>>`, a missing header is tolerated, but an invalid
+/// header is still rejected.
+pub struct GraphHeader<H, R = GraphHeaderRejection>(pub H, PhantomData<fn() -> R>);
+
+impl<H, R> GraphHeader<H, R> {
+    /// Create a new extractor value wrapping the given header.
+    pub fn new(header: H) -> Self {
+        Self(header, PhantomData)
+    }
+
+    /// Get the inner typed header.
+    pub fn into_inner(self) -> H {
+        self.0
+    }
+}
+
+impl<H, R> Deref for GraphHeader<H, R> {
+    type Target = H;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl<H: std::fmt::Debug, R> std::fmt::Debug for GraphHeader<H, R> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("GraphHeader").field(&self.0).finish()
+    }
+}
+
+impl<H, R, S> FromRequestParts<S> for GraphHeader<H, R>
+where
+    H: Header,
+    R: From<GraphHeaderRejection> + IntoResponse,
+    S: Send + Sync,
+{
+    type Rejection = R;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        match decode::<H>(parts)? {
+            Some(header) => Ok(Self::new(header)),
+            None => Err(GraphHeaderRejection::Missing { name: H::name() }.into()),
+        }
+    }
+}
+
+impl<H, R, S> OptionalFromRequestParts<S> for GraphHeader<H, R>
+where
+    H: Header,
+    R: From<GraphHeaderRejection> + IntoResponse,
+    S: Send + Sync,
+{
+    type Rejection = R;
+
+    async fn from_request_parts(
+        parts: &mut Parts,
+        _state: &S,
+    ) -> Result<Option<Self>, Self::Rejection> {
+        Ok(decode::<H>(parts)?.map(Self::new))
+    }
+}
+
+/// An extractor for _typed headers_ that never rejects the request.
+///
+/// Missing and invalid headers are tolerated. The extraction result contains the reason why the
+/// header could not be extracted.
+#[derive(Debug)]
+pub struct MaybeGraphHeader<H>(pub Result<H, GraphHeaderRejection>);
+
+impl<H, S> FromRequestParts<S> for MaybeGraphHeader<H>
+where
+    H: Header,
+    S: Send + Sync,
+{
+    type Rejection = Infallible;
+
+    async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
+        let header = decode::<H>(parts)
+            .and_then(|header| header.ok_or(GraphHeaderRejection::Missing { name: H::name() }));
+        Ok(Self(header))
+    }
+}
+
+/// Implement [`IntoResponseParts`] for a _typed header_, inserting it into the response headers.
+macro_rules! impl_into_response_parts {
+    ($($(#[$meta:meta])* $header:ty),* $(,)?) => {
+        $(
+            $(#[$meta])*
+            impl IntoResponseParts for $header {
+                type Error = Infallible;
+
+                fn into_response_parts(
+                    self,
+                    mut res: ResponseParts,
+                ) -> Result<ResponseParts, Self::Error> {
+                    res.headers_mut().typed_insert(self);
+                    Ok(res)
+                }
+            }
+        )*
+    };
+}
+
+impl_into_response_parts!(
+    GraphAttestable,
+    GraphIndexed,
+    #[cfg(feature = "attestation")]
+    GraphAttestation,
+    #[cfg(feature = "attestation")]
+    CompactGraphAttestation,
+);
+
+#[cfg(test)]
+mod tests {
+    use ::axum::{Router, body::Body, routing::get};
+    use headers::HeaderMapExt as _;
+    use http::{Request, StatusCode};
+    use thegraph_core::alloy::primitives::BlockHash;
+    use tower::ServiceExt as _;
+
+    use super::{GraphHeader, GraphHeaderRejection, MaybeGraphHeader};
+    use crate::{
+        graph_attestable::GraphAttestable,
+        graph_indexed::{BlockInfo, GraphIndexed},
+    };
+
+    /// A service-specific rejection type
+    struct TeapotRejection;
+
+    impl From<GraphHeaderRejection> for TeapotRejection {
+        fn from(_: GraphHeaderRejection) -> Self {
+            Self
+        }
+    }
+
+    impl ::axum::response::IntoResponse for TeapotRejection {
+        fn into_response(self) -> ::axum::response::Response {
+            StatusCode::IM_A_TEAPOT.into_response()
+        }
+    }
+
+    /// Create a test router with the different extractors
+    fn router() -> Router {
+        Router::new()
+            .route(
+                "/strict",
+                get(|GraphHeader(header, ..): GraphHeader<GraphAttestable>| async move {
+                    header.0.to_string()
+                }),
+            )
+            .route(
+                "/custom",
+                get(
+                    |GraphHeader(header, ..): GraphHeader<GraphAttestable, TeapotRejection>| async move {
+                        header.0.to_string()
+                    },
+                ),
+            )
+            .route(
+                "/optional",
+                get(|header: Option<GraphHeader<GraphAttestable>>| async move {
+                    format!("{:?}", header.map(|GraphHeader(header, ..)| header.0))
+                }),
+            )
+            .route(
+                "/tolerant",
+                get(|MaybeGraphHeader(header): MaybeGraphHeader<GraphAttestable>| async move {
+                    match header {
+                        Ok(header) => header.0.to_string(),
+                        Err(GraphHeaderRejection::Missing { .. }) => "missing".to_string(),
+                        Err(GraphHeaderRejection::Invalid { .. }) => "invalid".to_string(),
+                    }
+                }),
+            )
+            .route(
+                "/respond",
+                get(|| async {
+                    let info = BlockInfo {
+                        hash: BlockHash::new([0x55; 32]),
+                        number: 42,
+                        timestamp: None,
+                    };
+                    (GraphAttestable(true), GraphIndexed(info), "ok")
+                }),
+            )
+    }
+
+    /// Send a request to the test router, optionally with a `graph-attestable` header value
+    async fn send(
+        uri: &str,
+        header: Option<&'static str>,
+    ) -> (StatusCode, http::HeaderMap, String) {
+        let mut request = Request::get(uri);
+        if let Some(value) = header {
+            request = request.header(crate::graph_attestable::HEADER_NAME, value);
+        }
+        let request = request.body(Body::empty()).expect("valid request");
+
+        let response = router().oneshot(request).await.expect("infallible");
+        let (parts, body) = response.into_parts();
+        let body = ::axum::body::to_bytes(body, usize::MAX)
+            .await
+            .expect("valid body");
+        (
+            parts.status,
+            parts.headers,
+            String::from_utf8(body.to_vec()).expect("valid utf-8"),
+        )
+    }
+
+    #[tokio::test]
+    async fn strict_extractor_rejects_missing_and_invalid_headers() {
+        //* When
+        let valid = send("/strict", Some("true")).await;
+        let missing = send("/strict", None).await;
+        let invalid = send("/strict", Some("maybe")).await;
+
+        //* Then
+        assert_eq!(valid.0, StatusCode::OK);
+        assert_eq!(valid.2, "true");
+
+        assert_eq!(missing.0, StatusCode::BAD_REQUEST);
+        assert_eq!(missing.2, "missing `graph-attestable` header");
+
+        assert_eq!(invalid.0, StatusCode::BAD_REQUEST);
+        assert_eq!(invalid.2, "invalid `graph-attestable` header");
+    }
+
+    #[tokio::test]
+    async fn strict_extractor_uses_the_configured_rejection() {
+        //* When
+        let missing = send("/custom", None).await;
+
+        //* Then
+        assert_eq!(missing.0, StatusCode::IM_A_TEAPOT);
+    }
+
+    #[tokio::test]
+    async fn optional_extractor_tolerates_missing_headers() {
+        //* When
+        let valid = send("/optional", Some("false")).await;
+        let missing = send("/optional", None).await;
+        let invalid = send("/optional", Some("maybe")).await;
+
+        //* Then
+        assert_eq!(valid.2, "Some(false)");
+        assert_eq!(missing.2, "None");
+        assert_eq!(invalid.0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn tolerant_extractor_never_rejects() {
+        //* When
+        let valid = send("/tolerant", Some("true")).await;
+        let missing = send("/tolerant", None).await;
+        let invalid = send("/tolerant", Some("maybe")).await;
+
+        //* Then
+        assert_eq!(valid.2, "true");
+        assert_eq!(missing.0, StatusCode::OK);
+        assert_eq!(missing.2, "missing");
+        assert_eq!(invalid.0, StatusCode::OK);
+        assert_eq!(invalid.2, "invalid");
+    }
+
+    #[tokio::test]
+    async fn typed_headers_are_inserted_into_responses() {
+        //* When
+        let (status, headers, _) = send("/respond", None).await;
+
+        //* Then
+        assert_eq!(status, StatusCode::OK);
+        assert!(matches!(
+            headers.typed_get::<GraphAttestable>(),
+            Some(GraphAttestable(true))
+        ));
+        assert!(matches!(
+            headers.typed_get::<GraphIndexed>(),
+            Some(GraphIndexed(BlockInfo { number: 42, .. }))
+        ));
+    }
+}
@@ -0,0 +1,149 @@
+use headers::{HeaderName, HeaderValue};
+
+/// Errors that can occur when decoding a _typed header_ value.
+#[derive(Debug, thiserror::Error)]
+#[non_exhaustive]
+pub enum DecodeError {
+    /// The header value is not valid JSON, or does not match the expected JSON structure
+    #[error("invalid json: {0}")]
+    Json(#[from] serde_json::Error),
+
+    /// The header value is not valid base64
+    #[error("invalid base64: {0}")]
+    Base64(#[from] base64::DecodeError),
+
+    /// The header has no values
+    #[error("missing header value")]
+    MissingValue,
+
+    /// The header has multiple values, but only one is allowed
+    #[error("multiple header values")]
+    MultipleValues,
+
+    /// The header value exceeds the maximum allowed size
+    #[error("header value too large, max {max} bytes, got {actual}")]
+    TooLarge {
+        /// The maximum size in bytes
+        max: usize,
+        /// The actual size in bytes
+        actual: usize,
+    },
+
+    /// The header value is not one of the expected values
+    #[error("unexpected value, expected {expected}")]
+    UnexpectedValue {
+        /// A description of the expected values
+        expected: &'static str,
+    },
+
+    /// The header value could not be parsed
+    #[error("invalid value: {0}")]
+    InvalidValue(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// The decoded header value has an invalid length
+    #[error("invalid length, expected {expected} bytes, got {actual}")]
+    InvalidLength {
+        /// The expected length in bytes
+        expected: usize,
+        /// The actual length in bytes
+        actual: usize,
+    },
+}
+
+/// A _typed header_ whose values can be decoded with a detailed [`DecodeError`].
+///
+/// Unlike `headers::Header::decode`, which returns an opaque `headers::Error`, the decoding
+/// errors report the reason why the header could not be decoded.
+pub trait DecodeValue: headers::Header + Sized {
+    /// Decode the typed header from a header value.
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError>;
+
+    /// Decode the typed header from all the header values.
+    ///
+    /// By default, the first header value is decoded, and the rest are ignored.
+    fn decode_values<'i, I>(values: &mut I) -> Result<Self, DecodeError>
+    where
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or(DecodeError::MissingValue)?;
+        Self::decode_value(value)
+    }
+}
+
+/// An invalid _typed header_.
+#[derive(Debug, thiserror::Error)]
+#[error("invalid `{name}` header: {source}")]
+pub struct InvalidHeader {
+    /// The name of the header
+    pub name: &'static HeaderName,
+    /// The reason why the header value could not be decoded
+    #[source]
+    pub source: DecodeError,
+}
+
+/// Implement `headers::Header` for a _typed header_ wrapping a value with a string form.
+///
+/// The first header value is decoded via the type's [`DecodeValue`] implementation, and the
+/// wrapped value is encoded via its `Display` implementation. Sensitive headers, e.g., secrets,
+/// are marked as such on encode.
+macro_rules! impl_string_header {
+    ($name:ty, $header_name:expr $(, sensitive = $sensitive:literal)?) => {
+        impl headers::Header for $name {
+            fn name() -> &'static headers::HeaderName {
+                static HTTP_HEADER_NAME: headers::HeaderName =
+                    headers::HeaderName::from_static($header_name);
+                &HTTP_HEADER_NAME
+            }
+
+            fn decode<'i, I>(values: &mut I) -> Result<Self, headers::Error>
+            where
+                Self: Sized,
+                I: Iterator<Item = &'i headers::HeaderValue>,
+            {
+                let value = values.next().ok_or_else(headers::Error::invalid)?;
+                <Self as $crate::decode::DecodeValue>::decode_value(value)
+                    .map_err(|_| headers::Error::invalid())
+            }
+
+            fn encode<E: Extend<headers::HeaderValue>>(&self, values: &mut E) {
+                #[allow(unused_mut)]
+                let mut value = headers::HeaderValue::from_str(&self.0.to_string())
+                    .expect("header to be valid ascii");
+                $(value.set_sensitive($sensitive);)?
+                values.extend(std::iter::once(value));
+            }
+        }
+    };
+}
+
+pub(crate) use impl_string_header;
+
+/// Get the typed header from the header map, if present.
+pub(crate) fn get<H: DecodeValue>(headers: &http::HeaderMap) -> Result<Option<H>, InvalidHeader> {
+    let mut values = headers.get_all(H::name()).iter().peekable();
+    if values.peek().is_none() {
+        return Ok(None);
+    }
+
+    H::decode_values(&mut values)
+        .map(Some)
+        .map_err(|source| InvalidHeader {
+            name: H::name(),
+            source,
+        })
+}
+
+/// Whether the header value bytes are a JSON object, as opposed to a base64-encoded value.
+///
+/// A JSON object always starts with a `{` character, which is not part of the base64 alphabet.
+#[cfg(any(feature = "attestation", feature = "tally"))]
+pub(crate) fn is_json_object(bytes: &[u8]) -> bool {
+    bytes.trim_ascii_start().starts_with(b"{")
+}
+
+/// Get the header value as a string, rejecting non-visible ASCII characters.
+pub(crate) fn to_str(value: &HeaderValue) -> Result<&str, DecodeError> {
+    value.to_str().map_err(|_| DecodeError::UnexpectedValue {
+        expected: "a visible ASCII string",
+    })
+}
@@ -0,0 +1,152 @@
+//! An HTTP _typed header_ for the `graph-api-key` header.
+//!
+//! The `graph-api-key` header contains the client's [`ApiKey`], a 32-character lowercase
+//! hexadecimal string, e.g., `0123456789abcdef0123456789abcdef`.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_headers::graph_api_key::{ApiKey, GraphApiKey, HEADER_NAME};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let value: ApiKey = "0123456789abcdef0123456789abcdef".parse().unwrap();
+//!
+//! // Insert a `graph-api-key` HTTP header
+//! header_map.typed_insert(GraphApiKey(value));
+//!
+//! // Get the `graph-api-key` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `graph-api-key` HTTP header by type
+//! let header_typed = header_map.typed_get::<GraphApiKey>();
+//! assert!(matches!(header_typed, Some(GraphApiKey(..))));
+//! ```
+
+use headers::HeaderValue;
+use thegraph_core::alloy::hex;
+
+use crate::decode::{self, DecodeError, DecodeValue};
+
+/// The HTTP header name for the `graph-api-key` header.
+pub const HEADER_NAME: &str = "graph-api-key";
+
+/// API key parsing error.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, thiserror::Error)]
+#[error("invalid API key (expected 32 lowercase hexadecimal characters)")]
+pub struct InvalidApiKey;
+
+/// A client's API key.
+///
+/// An API key is a 16-byte value, represented as a 32-character lowercase hexadecimal string.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct ApiKey([u8; 16]);
+
+impl ApiKey {
+    /// Create a new API key from its bytes.
+    pub const fn new(bytes: [u8; 16]) -> Self {
+        Self(bytes)
+    }
+
+    /// Get the bytes of the API key.
+    pub fn as_bytes(&self) -> &[u8; 16] {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for ApiKey {
+    type Err = InvalidApiKey;
+
+    /// Parse an API key from a 32-character lowercase hexadecimal string.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.len() != 32 || !s.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f')) {
+            return Err(InvalidApiKey);
+        }
+        hex::decode_to_array(s).map(Self).map_err(|_| InvalidApiKey)
+    }
+}
+
+impl std::fmt::Display for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&hex::encode(self.0))
+    }
+}
+
+/// The API key is a secret, so only its first characters are shown.
+impl std::fmt::Debug for ApiKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ApiKey({}..)", hex::encode(&self.0[..2]))
+    }
+}
+
+/// An HTTP _typed header_ for the `graph-api-key` header.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphApiKey(pub ApiKey);
+
+decode::impl_string_header!(GraphApiKey, HEADER_NAME, sensitive = true);
+
+impl DecodeValue for GraphApiKey {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        let api_key = decode::to_str(value)?
+            .parse::<ApiKey>()
+            .map_err(|err| DecodeError::InvalidValue(err.into()))?;
+        Ok(Self(api_key))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+    use proptest::prelude::*;
+
+    use super::{ApiKey, GraphApiKey};
+    use crate::decode::DecodeValue;
+
+    proptest! {
+        #[test]
+        fn encode_decode_round_trip(bytes in any::<[u8; 16]>()) {
+            //* Given
+            let header = GraphApiKey(ApiKey::new(bytes));
+
+            //* When
+            let mut values = vec![];
+            header.encode(&mut values);
+            let decoded = GraphApiKey::decode(&mut values.iter());
+
+            //* Then
+            prop_assert!(values[0].is_sensitive());
+            prop_assert_eq!(decoded.expect("header to be valid"), header);
+        }
+
+        #[test]
+        fn reject_invalid_api_keys(value in "[0-9a-fA-F]{0,31}|[0-9a-f]{33,40}|[0-9a-f]{31}[A-Fg-z]") {
+            //* When
+            let result = value.parse::<ApiKey>();
+
+            //* Then
+            prop_assert!(result.is_err());
+        }
+    }
+
+    #[test]
+    fn fail_decode_uppercase_api_key() {
+        //* Given
+        let value = HeaderValue::from_static("0123456789ABCDEF0123456789ABCDEF");
+
+        //* When
+        let result = GraphApiKey::decode_value(&value);
+
+        //* Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn api_key_debug_is_redacted() {
+        //* Given
+        let api_key: ApiKey = "0123456789abcdef0123456789abcdef".parse().unwrap();
+
+        //* Then
+        assert_eq!(format!("{api_key:?}"), "ApiKey(0123..)");
+    }
+}
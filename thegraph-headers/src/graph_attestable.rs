@@ -25,6 +25,8 @@
 
 use headers::{Error as HeaderError, Header, HeaderName, HeaderValue};
 
+use crate::decode::{DecodeError, DecodeValue};
+
 /// The HTTP header name for the `graph-attestable` header.
 pub const HEADER_NAME: &str = "graph-attestable";
 
@@ -48,13 +50,7 @@ impl Header for GraphAttestable {
         I: Iterator<Item = &'i HeaderValue>,
     {
         let value = values.next().ok_or_else(HeaderError::invalid)?;
-        if value == "true" {
-            Ok(Self(true))
-        } else if value == "false" {
-            Ok(Self(false))
-        } else {
-            Err(HeaderError::invalid())
-        }
+        Self::decode_value(value).map_err(|_| HeaderError::invalid())
     }
 
     fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
@@ -66,3 +62,17 @@ impl Header for GraphAttestable {
         values.extend(std::iter::once(value));
     }
 }
+
+impl DecodeValue for GraphAttestable {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        if value == "true" {
+            Ok(Self(true))
+        } else if value == "false" {
+            Ok(Self(false))
+        } else {
+            Err(DecodeError::UnexpectedValue {
+                expected: "`true` or `false`",
+            })
+        }
+    }
+}
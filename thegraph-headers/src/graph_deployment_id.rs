@@ -0,0 +1,117 @@
+//! An HTTP _typed header_ for the `graph-deployment-id` header.
+//!
+//! The `graph-deployment-id` header contains the [`DeploymentId`] of the subgraph deployment a
+//! request targets, in its canonical CIDv0 string form, e.g.,
+//! `QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz`.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_core::deployment_id;
+//! use thegraph_headers::graph_deployment_id::{GraphDeploymentId, HEADER_NAME};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let value = deployment_id!("QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz");
+//!
+//! // Insert a `graph-deployment-id` HTTP header
+//! header_map.typed_insert(GraphDeploymentId(value));
+//!
+//! // Get the `graph-deployment-id` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `graph-deployment-id` HTTP header by type
+//! let header_typed = header_map.typed_get::<GraphDeploymentId>();
+//! assert!(matches!(header_typed, Some(GraphDeploymentId(..))));
+//! ```
+
+use headers::HeaderValue;
+pub use thegraph_core::DeploymentId;
+
+use crate::decode::{self, DecodeError, DecodeValue};
+
+/// The HTTP header name for the `graph-deployment-id` header.
+pub const HEADER_NAME: &str = "graph-deployment-id";
+
+/// An HTTP _typed header_ for the `graph-deployment-id` header.
+///
+/// The `graph-deployment-id` header contains a [`DeploymentId`] in its canonical CIDv0 string
+/// form. Other representations, e.g., the 32-byte hex string, are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphDeploymentId(pub DeploymentId);
+
+decode::impl_string_header!(GraphDeploymentId, HEADER_NAME);
+
+impl DecodeValue for GraphDeploymentId {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        let value = decode::to_str(value)?;
+        if !value.starts_with("Qm") {
+            return Err(DecodeError::UnexpectedValue {
+                expected: "a CIDv0 deployment ID",
+            });
+        }
+
+        let deployment = value
+            .parse::<DeploymentId>()
+            .map_err(|err| DecodeError::InvalidValue(err.into()))?;
+        Ok(Self(deployment))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+    use proptest::prelude::*;
+    use thegraph_core::{DeploymentId, alloy::primitives::B256, deployment_id};
+
+    use super::GraphDeploymentId;
+    use crate::decode::{DecodeError, DecodeValue};
+
+    proptest! {
+        #[test]
+        fn encode_decode_round_trip(bytes in any::<[u8; 32]>()) {
+            //* Given
+            let header = GraphDeploymentId(DeploymentId::new(B256::new(bytes)));
+
+            //* When
+            let mut values = vec![];
+            header.encode(&mut values);
+            let decoded = GraphDeploymentId::decode(&mut values.iter());
+
+            //* Then
+            prop_assert_eq!(decoded.expect("header to be valid"), header);
+        }
+    }
+
+    #[test]
+    fn encode_deployment_id_as_cid_v0() {
+        //* Given
+        let deployment = deployment_id!("QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz");
+
+        let mut values = vec![];
+
+        //* When
+        GraphDeploymentId(deployment).encode(&mut values);
+
+        //* Then
+        assert_eq!(values[0], "QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz");
+    }
+
+    #[test]
+    fn fail_decode_non_canonical_deployment_ids() {
+        //* Given
+        let hex = HeaderValue::from_static(
+            "7d5a99f603f231d53a4f39d1521f98d2e8bb279cf29bebfd0687dc98458e7f89",
+        );
+        let invalid = HeaderValue::from_static("QmInvalid");
+
+        //* When
+        let hex = GraphDeploymentId::decode_value(&hex);
+        let invalid = GraphDeploymentId::decode_value(&invalid);
+
+        //* Then
+        assert!(matches!(hex, Err(DecodeError::UnexpectedValue { .. })));
+        assert!(matches!(invalid, Err(DecodeError::InvalidValue(_))));
+    }
+}
@@ -28,40 +28,24 @@
 //! assert!(matches!(header_typed, Some(GraphIndexed(..))));
 //! ```
 
-use headers::{Error as HeaderError, Header, HeaderName, HeaderValue};
-use thegraph_core::alloy::primitives::{BlockHash, BlockNumber};
+use std::{cmp::Ordering, time::SystemTime};
+
+use thegraph_core::{
+    BlockPointer,
+    alloy::primitives::{BlockHash, BlockNumber},
+};
 
 /// The HTTP header name for the `graph-indexed` header.
 pub const HEADER_NAME: &str = "graph-indexed";
 
-/// An HTTP _typed header_ for the `graph-indexed` header.
-///
-/// The `graph-indexed` header contains a JSON-encoded [`BlockInfo`] struct indicating the latest
-/// indexed block information.
-pub struct GraphIndexed(pub BlockInfo);
-
-impl Header for GraphIndexed {
-    fn name() -> &'static HeaderName {
-        static HTTP_HEADER_NAME: HeaderName = HeaderName::from_static(HEADER_NAME);
-        &HTTP_HEADER_NAME
-    }
-
-    fn decode<'i, I>(values: &mut I) -> Result<Self, HeaderError>
-    where
-        Self: Sized,
-        I: Iterator<Item = &'i HeaderValue>,
-    {
-        let value = values.next().ok_or_else(HeaderError::invalid)?;
-        let info = serde_json::from_slice::<'_, BlockInfo>(value.as_bytes())
-            .map_err(|_| HeaderError::invalid())?;
-        Ok(Self(info))
-    }
-
-    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
-        let bytes = serde_json::to_vec(&self.0).expect("header to be valid json");
-        let value = HeaderValue::from_bytes(&bytes).expect("header to be valid utf-8");
-        values.extend(std::iter::once(value));
-    }
+crate::json_header! {
+    /// An HTTP _typed header_ for the `graph-indexed` header.
+    ///
+    /// The `graph-indexed` header contains a JSON-encoded [`BlockInfo`] struct indicating the
+    /// latest indexed block information.
+    #[derive(Debug, Clone)]
+    pub struct GraphIndexed(pub BlockInfo);
+    name = HEADER_NAME;
 }
 
 /// A struct containing information about the latest block.
@@ -71,7 +55,7 @@ impl Header for GraphIndexed {
 /// See Graph Node's [`LatestBlockInfo`][1].
 ///
 /// [1]: https://github.com/graphprotocol/graph-node/blob/a8b590f7d3fbabf2968ce7ced30bfd1485ce5f31/graph/src/data/query/result.rs#L68-L74
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 pub struct BlockInfo {
     /// The hash of the latest block.
     pub hash: BlockHash,
@@ -81,3 +65,161 @@ pub struct BlockInfo {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<u64>,
 }
+
+impl BlockInfo {
+    /// Create a new block information struct from a block pointer and an optional timestamp.
+    pub fn new(pointer: BlockPointer, timestamp: Option<u64>) -> Self {
+        Self {
+            hash: pointer.hash,
+            number: pointer.number,
+            timestamp,
+        }
+    }
+
+    /// Get the block pointer of the block.
+    pub fn pointer(&self) -> BlockPointer {
+        BlockPointer {
+            number: self.number,
+            hash: self.hash,
+        }
+    }
+
+    /// Split the block information into its block pointer and timestamp.
+    pub fn into_parts(self) -> (BlockPointer, Option<u64>) {
+        (self.pointer(), self.timestamp)
+    }
+
+    /// Get the number of blocks the block is behind the given chain head.
+    ///
+    /// Returns `0` if the block is ahead of the chain head.
+    pub fn blocks_behind(&self, head: BlockNumber) -> u64 {
+        head.saturating_sub(self.number)
+    }
+
+    /// Get the number of seconds the block is behind the given current time.
+    ///
+    /// Returns `None` if the block has no timestamp, and `0` if the block timestamp is in the
+    /// future.
+    pub fn seconds_behind(&self, now: SystemTime) -> Option<u64> {
+        let timestamp = self.timestamp?;
+        let now = now
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .map(|now| now.as_secs())
+            .unwrap_or_default();
+        Some(now.saturating_sub(timestamp))
+    }
+}
+
+impl From<BlockPointer> for BlockInfo {
+    fn from(pointer: BlockPointer) -> Self {
+        Self::new(pointer, None)
+    }
+}
+
+/// Convert the block information into a block pointer, discarding the timestamp.
+///
+/// Use [`BlockInfo::into_parts`] to keep the timestamp.
+impl From<BlockInfo> for BlockPointer {
+    fn from(info: BlockInfo) -> Self {
+        info.pointer()
+    }
+}
+
+impl From<&BlockInfo> for BlockPointer {
+    fn from(info: &BlockInfo) -> Self {
+        info.pointer()
+    }
+}
+
+/// Block information is compared to block pointers by block number and hash, the timestamp is
+/// ignored.
+impl PartialEq<BlockPointer> for BlockInfo {
+    fn eq(&self, other: &BlockPointer) -> bool {
+        self.number == other.number && self.hash == other.hash
+    }
+}
+
+impl PartialEq<BlockInfo> for BlockPointer {
+    fn eq(&self, other: &BlockInfo) -> bool {
+        other == self
+    }
+}
+
+/// Block information is ordered relative to block pointers by block number, and then by hash,
+/// consistently with the [`BlockPointer`] ordering.
+impl PartialOrd<BlockPointer> for BlockInfo {
+    fn partial_cmp(&self, other: &BlockPointer) -> Option<Ordering> {
+        Some(
+            self.number
+                .cmp(&other.number)
+                .then_with(|| self.hash.cmp(&other.hash)),
+        )
+    }
+}
+
+impl PartialOrd<BlockInfo> for BlockPointer {
+    fn partial_cmp(&self, other: &BlockInfo) -> Option<Ordering> {
+        other.partial_cmp(self).map(Ordering::reverse)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use thegraph_core::{BlockPointer, alloy::primitives::BlockHash};
+
+    use super::BlockInfo;
+
+    /// Create a block pointer for testing
+    fn pointer(number: u64) -> BlockPointer {
+        BlockPointer {
+            number,
+            hash: BlockHash::new([0x55; 32]),
+        }
+    }
+
+    #[test]
+    fn convert_block_info_from_and_into_block_pointer() {
+        //* Given
+        let info = BlockInfo::new(pointer(42), Some(1_700_000_000));
+
+        //* When
+        let (block, timestamp) = info.clone().into_parts();
+
+        //* Then
+        assert_eq!(block, pointer(42));
+        assert_eq!(timestamp, Some(1_700_000_000));
+        assert_eq!(BlockInfo::new(block, timestamp), info);
+
+        assert_eq!(BlockPointer::from(&info), pointer(42));
+        assert_eq!(BlockInfo::from(pointer(42)).timestamp, None);
+    }
+
+    #[test]
+    fn compare_block_info_with_block_pointer() {
+        //* Given
+        let info = BlockInfo::new(pointer(42), Some(1_700_000_000));
+
+        //* Then
+        assert_eq!(info, pointer(42));
+        assert_eq!(pointer(42), info);
+        assert!(info < pointer(43));
+        assert!(info > pointer(41));
+        assert!(pointer(43) > info);
+    }
+
+    #[test]
+    fn measure_block_info_staleness() {
+        //* Given
+        let info = BlockInfo::new(pointer(42), Some(1_700_000_000));
+        let now = SystemTime::UNIX_EPOCH + Duration::from_secs(1_700_000_012);
+
+        //* Then
+        assert_eq!(info.blocks_behind(50), 8);
+        assert_eq!(info.blocks_behind(40), 0);
+        assert_eq!(info.seconds_behind(now), Some(12));
+        assert_eq!(info.seconds_behind(SystemTime::UNIX_EPOCH), Some(0));
+        assert_eq!(BlockInfo::from(pointer(42)).seconds_behind(now), None);
+    }
+}
@@ -0,0 +1,249 @@
+//! An HTTP _typed header_ for the `graph-min-block` header.
+//!
+//! The `graph-min-block` header contains the [`MinBlock`] a request requires, i.e., the request
+//! must be served from a block at or after it. The constraint is either a block number, e.g.,
+//! `42`, or a block pointer, i.e., a block number and a `0x`-prefixed lowercase hex block hash
+//! separated by a colon, e.g., `42:0x5555…5555`.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_headers::graph_min_block::{GraphMinBlock, HEADER_NAME, MinBlock};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let value = MinBlock::Number(42);
+//!
+//! // Insert a `graph-min-block` HTTP header
+//! header_map.typed_insert(GraphMinBlock(value));
+//!
+//! // Get the `graph-min-block` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `graph-min-block` HTTP header by type
+//! let header_typed = header_map.typed_get::<GraphMinBlock>();
+//! assert!(matches!(header_typed, Some(GraphMinBlock(..))));
+//! ```
+
+use headers::HeaderValue;
+use thegraph_core::{
+    BlockPointer,
+    alloy::{
+        hex,
+        primitives::{BlockHash, BlockNumber},
+    },
+};
+
+use crate::decode::{self, DecodeError, DecodeValue};
+
+/// The HTTP header name for the `graph-min-block` header.
+pub const HEADER_NAME: &str = "graph-min-block";
+
+/// Minimum block constraint parsing error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidMinBlock {
+    /// The block number is not a canonical decimal number, i.e., it contains non-digit
+    /// characters or leading zeros, or it overflows.
+    #[error("invalid block number: {0}")]
+    InvalidNumber(String),
+
+    /// The block hash is not a `0x`-prefixed 64-character lowercase hex string.
+    #[error("invalid block hash: {0}")]
+    InvalidHash(String),
+}
+
+/// A minimum block constraint.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MinBlock {
+    /// The request must be served from a block with a number greater than or equal to this one.
+    Number(BlockNumber),
+    /// The request must be served from a block with a number greater than or equal to this
+    /// block's number. If served from the block with the same number, its hash must match.
+    Pointer(BlockPointer),
+}
+
+impl MinBlock {
+    /// Get the minimum block number.
+    pub fn number(&self) -> BlockNumber {
+        match self {
+            Self::Number(number) => *number,
+            Self::Pointer(pointer) => pointer.number,
+        }
+    }
+
+    /// Check whether the given block satisfies the constraint.
+    pub fn is_satisfied_by(&self, block: &BlockPointer) -> bool {
+        match self {
+            Self::Number(number) => block.number >= *number,
+            Self::Pointer(pointer) => {
+                block.number > pointer.number
+                    || (block.number == pointer.number && block.hash == pointer.hash)
+            }
+        }
+    }
+}
+
+impl From<BlockNumber> for MinBlock {
+    fn from(number: BlockNumber) -> Self {
+        Self::Number(number)
+    }
+}
+
+impl From<BlockPointer> for MinBlock {
+    fn from(pointer: BlockPointer) -> Self {
+        Self::Pointer(pointer)
+    }
+}
+
+impl std::str::FromStr for MinBlock {
+    type Err = InvalidMinBlock;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (number, hash) = match s.split_once(':') {
+            Some((number, hash)) => (number, Some(hash)),
+            None => (s, None),
+        };
+
+        let is_canonical_number = !number.is_empty()
+            && number.bytes().all(|b| b.is_ascii_digit())
+            && (number == "0" || !number.starts_with('0'));
+        let number = is_canonical_number
+            .then(|| number.parse::<BlockNumber>().ok())
+            .flatten()
+            .ok_or_else(|| InvalidMinBlock::InvalidNumber(number.to_string()))?;
+
+        let Some(hash) = hash else {
+            return Ok(Self::Number(number));
+        };
+
+        let hash = hash
+            .strip_prefix("0x")
+            .filter(|hex| {
+                hex.len() == 64 && hex.bytes().all(|b| matches!(b, b'0'..=b'9' | b'a'..=b'f'))
+            })
+            .and_then(|hex| hex::decode_to_array(hex).ok())
+            .map(BlockHash::new)
+            .ok_or_else(|| InvalidMinBlock::InvalidHash(hash.to_string()))?;
+        Ok(Self::Pointer(BlockPointer { number, hash }))
+    }
+}
+
+impl std::fmt::Display for MinBlock {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Number(number) => write!(f, "{number}"),
+            Self::Pointer(pointer) => write!(f, "{}:{}", pointer.number, pointer.hash),
+        }
+    }
+}
+
+/// An HTTP _typed header_ for the `graph-min-block` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphMinBlock(pub MinBlock);
+
+decode::impl_string_header!(GraphMinBlock, HEADER_NAME);
+
+impl DecodeValue for GraphMinBlock {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        let min_block = decode::to_str(value)?
+            .parse::<MinBlock>()
+            .map_err(|err| DecodeError::InvalidValue(err.into()))?;
+        Ok(Self(min_block))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+    use proptest::prelude::*;
+    use thegraph_core::{BlockPointer, alloy::primitives::BlockHash};
+
+    use super::{GraphMinBlock, InvalidMinBlock, MinBlock};
+    use crate::decode::DecodeValue;
+
+    /// A strategy generating arbitrary minimum block constraints
+    fn min_block() -> impl Strategy<Value = MinBlock> {
+        prop_oneof![
+            any::<u64>().prop_map(MinBlock::Number),
+            (any::<u64>(), any::<[u8; 32]>()).prop_map(|(number, hash)| {
+                MinBlock::Pointer(BlockPointer {
+                    number,
+                    hash: BlockHash::new(hash),
+                })
+            }),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn encode_decode_round_trip(min_block in min_block()) {
+            //* Given
+            let header = GraphMinBlock(min_block);
+
+            //* When
+            let mut values = vec![];
+            header.encode(&mut values);
+            let decoded = GraphMinBlock::decode(&mut values.iter());
+
+            //* Then
+            prop_assert_eq!(decoded.expect("header to be valid"), header);
+        }
+    }
+
+    #[test]
+    fn encode_min_block_number_and_pointer() {
+        //* Given
+        let pointer = BlockPointer {
+            number: 42,
+            hash: BlockHash::new([0x55; 32]),
+        };
+
+        //* Then
+        assert_eq!(MinBlock::Number(42).to_string(), "42");
+        assert_eq!(
+            MinBlock::Pointer(pointer).to_string(),
+            format!("42:0x{}", "55".repeat(32))
+        );
+    }
+
+    #[test]
+    fn fail_decode_non_canonical_min_blocks() {
+        //* Given
+        let invalid = ["", "042", "+42", "-1", "4_2", "18446744073709551616", "42:"];
+        let invalid_hash = [
+            format!("42:{}", "55".repeat(32)),
+            format!("42:0x{}", "AA".repeat(32)),
+            format!("42:0x{}", "55".repeat(31)),
+        ];
+
+        //* Then
+        for value in invalid {
+            let result = GraphMinBlock::decode_value(&HeaderValue::from_static(value));
+            assert!(result.is_err(), "{value:?} should be rejected");
+        }
+        for value in invalid_hash {
+            assert!(matches!(
+                value.parse::<MinBlock>(),
+                Err(InvalidMinBlock::InvalidHash(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn check_min_block_constraint() {
+        //* Given
+        let block = |number, hash| BlockPointer {
+            number,
+            hash: BlockHash::new([hash; 32]),
+        };
+        let min_block = MinBlock::Pointer(block(42, 0x55));
+
+        //* Then
+        assert!(min_block.is_satisfied_by(&block(42, 0x55)));
+        assert!(min_block.is_satisfied_by(&block(43, 0xaa)));
+        assert!(!min_block.is_satisfied_by(&block(42, 0xaa)));
+        assert!(!min_block.is_satisfied_by(&block(41, 0x55)));
+        assert!(MinBlock::Number(42).is_satisfied_by(&block(42, 0xaa)));
+    }
+}
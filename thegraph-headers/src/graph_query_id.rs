@@ -0,0 +1,160 @@
+//! An HTTP _typed header_ for the `graph-query-id` header.
+//!
+//! The `graph-query-id` header contains a [`QueryId`] identifying a query across the services
+//! handling it, e.g., the gateway and the indexer, for tracing purposes.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_headers::graph_query_id::{GraphQueryId, HEADER_NAME, QueryId};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let value: QueryId = "0f6f4dc4-5d4a-4c47-9a5e-0f1b2c3d4e5f".parse().unwrap();
+//!
+//! // Insert a `graph-query-id` HTTP header
+//! header_map.typed_insert(GraphQueryId(value));
+//!
+//! // Get the `graph-query-id` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `graph-query-id` HTTP header by type
+//! let header_typed = header_map.typed_get::<GraphQueryId>();
+//! assert!(matches!(header_typed, Some(GraphQueryId(..))));
+//! ```
+
+use headers::HeaderValue;
+
+use crate::decode::{self, DecodeError, DecodeValue};
+
+/// The HTTP header name for the `graph-query-id` header.
+pub const HEADER_NAME: &str = "graph-query-id";
+
+/// The maximum length of a query ID
+const QUERY_ID_MAX_LEN: usize = 128;
+
+/// Query ID parsing error.
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum InvalidQueryId {
+    /// The query ID is empty, or longer than 128 characters.
+    #[error("invalid length {0} (length must be between 1 and 128)")]
+    InvalidLength(usize),
+
+    /// The query ID contains characters other than ASCII alphanumerics, `-`, `_`, `.` and `:`.
+    #[error("invalid character {0:?}")]
+    InvalidCharacter(char),
+}
+
+/// A query ID, e.g., a UUID or a trace ID.
+///
+/// A query ID is a non-empty string of up to 128 ASCII alphanumerics, `-`, `_`, `.` and `:`
+/// characters.
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QueryId(String);
+
+impl QueryId {
+    /// Create a new query ID, validating its contents.
+    pub fn new(value: impl Into<String>) -> Result<Self, InvalidQueryId> {
+        let value = value.into();
+        if value.is_empty() || value.len() > QUERY_ID_MAX_LEN {
+            return Err(InvalidQueryId::InvalidLength(value.len()));
+        }
+        if let Some(c) = value
+            .chars()
+            .find(|c| !(c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.' | ':')))
+        {
+            return Err(InvalidQueryId::InvalidCharacter(c));
+        }
+        Ok(Self(value))
+    }
+
+    /// Get the query ID as a string slice.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl std::str::FromStr for QueryId {
+    type Err = InvalidQueryId;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Self::new(s)
+    }
+}
+
+impl std::fmt::Display for QueryId {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl AsRef<str> for QueryId {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+/// An HTTP _typed header_ for the `graph-query-id` header.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphQueryId(pub QueryId);
+
+decode::impl_string_header!(GraphQueryId, HEADER_NAME);
+
+impl DecodeValue for GraphQueryId {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        let query_id = decode::to_str(value)?
+            .parse::<QueryId>()
+            .map_err(|err| DecodeError::InvalidValue(err.into()))?;
+        Ok(Self(query_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+    use proptest::prelude::*;
+
+    use super::{GraphQueryId, InvalidQueryId, QueryId};
+    use crate::decode::DecodeValue;
+
+    proptest! {
+        #[test]
+        fn encode_decode_round_trip(value in "[A-Za-z0-9._:-]{1,128}") {
+            //* Given
+            let header = GraphQueryId(QueryId::new(value).expect("query ID to be valid"));
+
+            //* When
+            let mut values = vec![];
+            header.encode(&mut values);
+            let decoded = GraphQueryId::decode(&mut values.iter());
+
+            //* Then
+            prop_assert_eq!(decoded.expect("header to be valid"), header);
+        }
+
+        #[test]
+        fn reject_invalid_characters(value in "[A-Za-z0-9]{0,8}[ /=@+][A-Za-z0-9]{0,8}") {
+            //* When
+            let result = QueryId::new(value);
+
+            //* Then
+            prop_assert!(matches!(result, Err(InvalidQueryId::InvalidCharacter(_))));
+        }
+    }
+
+    #[test]
+    fn fail_decode_invalid_query_ids() {
+        //* Given
+        let empty = HeaderValue::from_static("");
+        let too_long = HeaderValue::from_str(&"a".repeat(129)).unwrap();
+
+        //* When
+        let empty = GraphQueryId::decode_value(&empty);
+        let too_long = GraphQueryId::decode_value(&too_long);
+
+        //* Then
+        assert!(empty.is_err());
+        assert!(too_long.is_err());
+    }
+}
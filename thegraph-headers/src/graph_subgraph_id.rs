@@ -0,0 +1,106 @@
+//! An HTTP _typed header_ for the `graph-subgraph-id` header.
+//!
+//! The `graph-subgraph-id` header contains the [`SubgraphId`] a request targets, as a
+//! base58-encoded string, e.g., `DZz4kDTdmzWLWsV373w2bSmoar3umKKH9y82SUKr5qmp`.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_core::subgraph_id;
+//! use thegraph_headers::graph_subgraph_id::{GraphSubgraphId, HEADER_NAME};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let value = subgraph_id!("DZz4kDTdmzWLWsV373w2bSmoar3umKKH9y82SUKr5qmp");
+//!
+//! // Insert a `graph-subgraph-id` HTTP header
+//! header_map.typed_insert(GraphSubgraphId(value));
+//!
+//! // Get the `graph-subgraph-id` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `graph-subgraph-id` HTTP header by type
+//! let header_typed = header_map.typed_get::<GraphSubgraphId>();
+//! assert!(matches!(header_typed, Some(GraphSubgraphId(..))));
+//! ```
+
+use headers::HeaderValue;
+pub use thegraph_core::SubgraphId;
+
+use crate::decode::{self, DecodeError, DecodeValue};
+
+/// The HTTP header name for the `graph-subgraph-id` header.
+pub const HEADER_NAME: &str = "graph-subgraph-id";
+
+/// An HTTP _typed header_ for the `graph-subgraph-id` header.
+///
+/// The `graph-subgraph-id` header contains a [`SubgraphId`] in its canonical base58 string form.
+/// Non-canonical representations, e.g., with superfluous leading `1` characters, are rejected.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphSubgraphId(pub SubgraphId);
+
+decode::impl_string_header!(GraphSubgraphId, HEADER_NAME);
+
+impl DecodeValue for GraphSubgraphId {
+    fn decode_value(value: &HeaderValue) -> Result<Self, DecodeError> {
+        let value = decode::to_str(value)?;
+        let subgraph = value
+            .parse::<SubgraphId>()
+            .map_err(|err| DecodeError::InvalidValue(err.into()))?;
+
+        // Reject non-canonical representations of the subgraph ID
+        if subgraph.to_string() != value {
+            return Err(DecodeError::UnexpectedValue {
+                expected: "a canonical base58-encoded subgraph ID",
+            });
+        }
+
+        Ok(Self(subgraph))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+    use proptest::prelude::*;
+    use thegraph_core::{SubgraphId, alloy::primitives::B256};
+
+    use super::GraphSubgraphId;
+    use crate::decode::{DecodeError, DecodeValue};
+
+    proptest! {
+        #[test]
+        fn encode_decode_round_trip(bytes in any::<[u8; 32]>()) {
+            //* Given
+            let header = GraphSubgraphId(SubgraphId::new(B256::new(bytes)));
+
+            //* When
+            let mut values = vec![];
+            header.encode(&mut values);
+            let decoded = GraphSubgraphId::decode(&mut values.iter());
+
+            //* Then
+            prop_assert_eq!(decoded.expect("header to be valid"), header);
+        }
+    }
+
+    #[test]
+    fn fail_decode_invalid_subgraph_ids() {
+        //* Given
+        let invalid = HeaderValue::from_static("0OIl");
+        let too_long = HeaderValue::from_static("DZz4kDTdmzWLWsV373w2bSmoar3umKKH9y82SUKr5qmpDZz4");
+        let non_canonical =
+            HeaderValue::from_static("1DZz4kDTdmzWLWsV373w2bSmoar3umKKH9y82SUKr5qmp");
+
+        //* When
+        let invalid = GraphSubgraphId::decode_value(&invalid);
+        let too_long = GraphSubgraphId::decode_value(&too_long);
+        let non_canonical = GraphSubgraphId::decode_value(&non_canonical);
+
+        //* Then
+        assert!(matches!(invalid, Err(DecodeError::InvalidValue(_))));
+        assert!(matches!(too_long, Err(DecodeError::InvalidValue(_))));
+        assert!(non_canonical.is_err());
+    }
+}
@@ -0,0 +1,354 @@
+//! Declarative definition of JSON-encoded HTTP _typed headers_.
+//!
+//! The [`json_header!`](crate::json_header!) macro generates a _typed header_ from a header name
+//! and a serde-serializable type. The generated type implements [`headers::Header`] and
+//! [`DecodeValue`], so it can be used with `headers::HeaderMapExt`, the crate's extension traits
+//! and extractors.
+//!
+//! The header encoding and decoding behavior can be tuned via [`JsonHeaderOptions`]:
+//!
+//! - [`MultipleValues`]: Which value to decode if the header has multiple values.
+//! - [`JsonHeaderOptions::max_size`]: The maximum size of the header value accepted on decode.
+//! - [`JsonEncoding`]: Whether the JSON is sent raw, or base64 encoded.
+//!
+//! # Example
+//!
+//! ```rust
+//! use headers::HeaderMapExt as _;
+//! use thegraph_headers::json_header::{JsonEncoding, JsonHeaderOptions, MultipleValues};
+//!
+//! #[derive(Debug, serde::Serialize, serde::Deserialize)]
+//! pub struct Indexer {
+//!     pub url: String,
+//! }
+//!
+//! thegraph_headers::json_header! {
+//!     /// An HTTP _typed header_ for the `x-indexer` header.
+//!     #[derive(Debug)]
+//!     pub struct IndexerHeader(pub Indexer);
+//!     name = "x-indexer";
+//!     options = JsonHeaderOptions::new()
+//!         .multiple_values(MultipleValues::Reject)
+//!         .max_size(1024)
+//!         .encoding(JsonEncoding::Base64);
+//! }
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! header_map.typed_insert(IndexerHeader(Indexer {
+//!     url: "https://indexer.example.com".to_string(),
+//! }));
+//!
+//! let header = header_map.typed_get::<IndexerHeader>();
+//! assert!(matches!(header, Some(IndexerHeader(Indexer { url })) if url == "https://indexer.example.com"));
+//! ```
+
+use base64::{Engine as _, prelude::BASE64_STANDARD};
+use headers::HeaderValue;
+use serde::{Serialize, de::DeserializeOwned};
+
+pub use crate::decode::{DecodeError, DecodeValue};
+
+/// Which value to decode if a header has multiple values.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum MultipleValues {
+    /// Decode the first value, and ignore the rest.
+    #[default]
+    First,
+    /// Decode the last value, and ignore the rest.
+    Last,
+    /// Reject headers with multiple values.
+    Reject,
+}
+
+/// The encoding of the JSON header value.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonEncoding {
+    /// The raw JSON string.
+    #[default]
+    Raw,
+    /// The base64-encoded JSON string.
+    Base64,
+}
+
+/// The options of a JSON-encoded _typed header_.
+///
+/// The options are built with `const` methods, so they can be used in `const` contexts.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonHeaderOptions {
+    multiple_values: MultipleValues,
+    max_size: Option<usize>,
+    encoding: JsonEncoding,
+}
+
+impl JsonHeaderOptions {
+    /// Create the default options: decode the first value, no size limit, and raw JSON.
+    pub const fn new() -> Self {
+        Self {
+            multiple_values: MultipleValues::First,
+            max_size: None,
+            encoding: JsonEncoding::Raw,
+        }
+    }
+
+    /// Set which value to decode if the header has multiple values.
+    pub const fn multiple_values(mut self, multiple_values: MultipleValues) -> Self {
+        self.multiple_values = multiple_values;
+        self
+    }
+
+    /// Set the maximum size, in bytes, of the header value accepted on decode.
+    pub const fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Set the encoding of the JSON header value.
+    pub const fn encoding(mut self, encoding: JsonEncoding) -> Self {
+        self.encoding = encoding;
+        self
+    }
+}
+
+// The following functions are only public so that the `json_header!` macro expansion can call
+// them. They are not part of the public API, and are hidden from the documentation.
+
+/// Select the header value to decode from the header values, according to the options.
+#[doc(hidden)]
+pub fn select_value<'i, I>(
+    values: &mut I,
+    options: &JsonHeaderOptions,
+) -> Result<&'i HeaderValue, DecodeError>
+where
+    I: Iterator<Item = &'i HeaderValue>,
+{
+    let first = values.next().ok_or(DecodeError::MissingValue)?;
+    match options.multiple_values {
+        MultipleValues::First => Ok(first),
+        MultipleValues::Last => Ok(values.last().unwrap_or(first)),
+        MultipleValues::Reject => match values.next() {
+            None => Ok(first),
+            Some(_) => Err(DecodeError::MultipleValues),
+        },
+    }
+}
+
+/// Decode a JSON header value, according to the options.
+#[doc(hidden)]
+pub fn decode_value<T: DeserializeOwned>(
+    value: &HeaderValue,
+    options: &JsonHeaderOptions,
+) -> Result<T, DecodeError> {
+    let bytes = value.as_bytes();
+    if let Some(max) = options.max_size {
+        if bytes.len() > max {
+            return Err(DecodeError::TooLarge {
+                max,
+                actual: bytes.len(),
+            });
+        }
+    }
+
+    let value = match options.encoding {
+        JsonEncoding::Raw => serde_json::from_slice(bytes)?,
+        JsonEncoding::Base64 => serde_json::from_slice(&BASE64_STANDARD.decode(bytes)?)?,
+    };
+    Ok(value)
+}
+
+/// Encode a value as a JSON header value, according to the options.
+///
+/// The maximum size option is not enforced on encode.
+#[doc(hidden)]
+pub fn encode_value<T: Serialize>(value: &T, options: &JsonHeaderOptions) -> HeaderValue {
+    let bytes = serde_json::to_vec(value).expect("header to be valid json");
+    match options.encoding {
+        JsonEncoding::Raw => HeaderValue::from_bytes(&bytes).expect("header to be valid utf-8"),
+        JsonEncoding::Base64 => HeaderValue::from_str(&BASE64_STANDARD.encode(bytes))
+            .expect("header to be valid base64"),
+    }
+}
+
+/// Generate a JSON-encoded HTTP _typed header_.
+///
+/// The macro takes a tuple struct definition wrapping a serde-serializable type, the header name,
+/// and, optionally, the [`JsonHeaderOptions`](crate::json_header::JsonHeaderOptions) `const`
+/// expression. See the [`json_header`](mod@crate::json_header) module for an example.
+#[macro_export]
+macro_rules! json_header {
+    (
+        $(#[$meta:meta])*
+        $vis:vis struct $name:ident($field_vis:vis $ty:ty);
+        name = $header_name:expr;
+        $(options = $options:expr;)?
+    ) => {
+        $(#[$meta])*
+        $vis struct $name($field_vis $ty);
+
+        impl $name {
+            /// The options of the JSON-encoded typed header.
+            const JSON_HEADER_OPTIONS: $crate::json_header::JsonHeaderOptions = {
+                #[allow(unused_variables)]
+                let options = $crate::json_header::JsonHeaderOptions::new();
+                $(let options = $options;)?
+                options
+            };
+        }
+
+        impl $crate::headers::Header for $name {
+            fn name() -> &'static $crate::headers::HeaderName {
+                static HTTP_HEADER_NAME: $crate::headers::HeaderName =
+                    $crate::headers::HeaderName::from_static($header_name);
+                &HTTP_HEADER_NAME
+            }
+
+            fn decode<'i, I>(values: &mut I) -> ::core::result::Result<Self, $crate::headers::Error>
+            where
+                Self: Sized,
+                I: Iterator<Item = &'i $crate::headers::HeaderValue>,
+            {
+                <Self as $crate::json_header::DecodeValue>::decode_values(values)
+                    .map_err(|_| $crate::headers::Error::invalid())
+            }
+
+            fn encode<E: Extend<$crate::headers::HeaderValue>>(&self, values: &mut E) {
+                let value = $crate::json_header::encode_value(&self.0, &Self::JSON_HEADER_OPTIONS);
+                values.extend(::core::iter::once(value));
+            }
+        }
+
+        impl $crate::json_header::DecodeValue for $name {
+            fn decode_value(
+                value: &$crate::headers::HeaderValue,
+            ) -> ::core::result::Result<Self, $crate::json_header::DecodeError> {
+                $crate::json_header::decode_value(value, &Self::JSON_HEADER_OPTIONS).map(Self)
+            }
+
+            fn decode_values<'i, I>(
+                values: &mut I,
+            ) -> ::core::result::Result<Self, $crate::json_header::DecodeError>
+            where
+                I: Iterator<Item = &'i $crate::headers::HeaderValue>,
+            {
+                let value = $crate::json_header::select_value(values, &Self::JSON_HEADER_OPTIONS)?;
+                <Self as $crate::json_header::DecodeValue>::decode_value(value)
+            }
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::{Header, HeaderValue};
+
+    use super::{DecodeError, DecodeValue, JsonEncoding, JsonHeaderOptions, MultipleValues};
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Payload {
+        value: u64,
+    }
+
+    crate::json_header! {
+        /// A raw JSON test header
+        #[derive(Debug, PartialEq)]
+        struct RawHeader(Payload);
+        name = "x-raw";
+    }
+
+    crate::json_header! {
+        /// A base64 JSON test header, with strict options
+        #[derive(Debug, PartialEq)]
+        struct StrictHeader(Payload);
+        name = "x-strict";
+        options = JsonHeaderOptions::new()
+            .multiple_values(MultipleValues::Reject)
+            .max_size(32)
+            .encoding(JsonEncoding::Base64);
+    }
+
+    crate::json_header! {
+        /// A raw JSON test header, decoding the last value
+        #[derive(Debug, PartialEq)]
+        struct LastHeader(Payload);
+        name = "x-last";
+        options = JsonHeaderOptions::new().multiple_values(MultipleValues::Last);
+    }
+
+    #[test]
+    fn encode_and_decode_raw_and_base64_json() {
+        //* Given
+        let mut raw = vec![];
+        let mut strict = vec![];
+
+        //* When
+        RawHeader(Payload { value: 42 }).encode(&mut raw);
+        StrictHeader(Payload { value: 42 }).encode(&mut strict);
+
+        //* Then
+        assert_eq!(raw[0], r#"{"value":42}"#);
+        assert_eq!(strict[0], "eyJ2YWx1ZSI6NDJ9");
+
+        assert_eq!(
+            RawHeader::decode(&mut raw.iter()).expect("header to be valid"),
+            RawHeader(Payload { value: 42 })
+        );
+        assert_eq!(
+            StrictHeader::decode(&mut strict.iter()).expect("header to be valid"),
+            StrictHeader(Payload { value: 42 })
+        );
+    }
+
+    #[test]
+    fn decode_according_to_multiple_values_option() {
+        //* Given
+        let raw = [
+            HeaderValue::from_static(r#"{"value":1}"#),
+            HeaderValue::from_static(r#"{"value":2}"#),
+        ];
+        let strict = [
+            HeaderValue::from_static("eyJ2YWx1ZSI6NDJ9"),
+            HeaderValue::from_static("eyJ2YWx1ZSI6NDJ9"),
+        ];
+
+        //* When
+        let first = RawHeader::decode_values(&mut raw.iter());
+        let last = LastHeader::decode_values(&mut raw.iter());
+        let rejected = StrictHeader::decode_values(&mut strict.iter());
+
+        //* Then
+        assert_eq!(first.unwrap(), RawHeader(Payload { value: 1 }));
+        assert_eq!(last.unwrap(), LastHeader(Payload { value: 2 }));
+        assert!(matches!(rejected, Err(DecodeError::MultipleValues)));
+    }
+
+    #[test]
+    fn fail_decode_values_exceeding_max_size() {
+        //* Given
+        let values = [HeaderValue::from_static(
+            "eyJ2YWx1ZSI6NDIsInBhZGRpbmciOiJwYWRkaW5nIn0=",
+        )];
+
+        //* When
+        let result = StrictHeader::decode_values(&mut values.iter());
+
+        //* Then
+        assert!(matches!(
+            result,
+            Err(DecodeError::TooLarge {
+                max: 32,
+                actual: 44
+            })
+        ));
+    }
+
+    #[test]
+    fn fail_decode_if_no_values() {
+        //* Given
+        let values: [HeaderValue; 0] = [];
+
+        //* When
+        let result = RawHeader::decode_values(&mut values.iter());
+
+        //* Then
+        assert!(matches!(result, Err(DecodeError::MissingValue)));
+    }
+}
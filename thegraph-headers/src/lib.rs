@@ -10,11 +10,30 @@
 
 pub use headers;
 
+#[cfg(feature = "axum")]
+#[cfg_attr(docsrs, doc(cfg(feature = "axum")))]
+pub mod axum;
+mod decode;
+pub mod graph_api_key;
 pub mod graph_attestable;
 #[cfg(feature = "attestation")]
 #[cfg_attr(docsrs, doc(cfg(feature = "attestation")))]
 pub mod graph_attestation;
+pub mod graph_deployment_id;
 pub mod graph_indexed;
+pub mod graph_min_block;
+pub mod graph_query_id;
+pub mod graph_subgraph_id;
 mod http_ext;
+pub mod json_header;
+mod response_ext;
+#[cfg(feature = "tally")]
+#[cfg_attr(docsrs, doc(cfg(feature = "tally")))]
+pub mod tap_receipt;
+#[cfg(all(feature = "attestation", feature = "tower"))]
+#[cfg_attr(docsrs, doc(cfg(all(feature = "attestation", feature = "tower"))))]
+pub mod tower;
 
+pub use decode::{DecodeError, DecodeValue, InvalidHeader};
 pub use http_ext::HttpBuilderExt;
+pub use response_ext::{GraphHeaders, GraphHeadersExt};
@@ -0,0 +1,176 @@
+use crate::{
+    decode::{self, InvalidHeader},
+    graph_attestable::GraphAttestable,
+    graph_indexed::GraphIndexed,
+};
+
+/// The _The Graph_ typed headers of a response.
+///
+/// Each field contains the decoded header, `None` if the header is missing, or an
+/// [`InvalidHeader`] error with the reason why the header value could not be decoded.
+#[derive(Debug)]
+pub struct GraphHeaders {
+    /// The `graph-indexed` header
+    pub indexed: Result<Option<GraphIndexed>, InvalidHeader>,
+    /// The `graph-attestable` header
+    pub attestable: Result<Option<GraphAttestable>, InvalidHeader>,
+    /// The `graph-attestation` header
+    #[cfg(feature = "attestation")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "attestation")))]
+    pub attestation: Result<Option<crate::graph_attestation::GraphAttestation>, InvalidHeader>,
+}
+
+impl GraphHeaders {
+    /// Decode the _The Graph_ typed headers from a header map.
+    pub fn from_headers(headers: &http::HeaderMap) -> Self {
+        Self {
+            indexed: decode::get(headers),
+            attestable: decode::get(headers),
+            #[cfg(feature = "attestation")]
+            attestation: decode::get(headers),
+        }
+    }
+}
+
+/// An extension trait to extract _The Graph_ typed headers from `http::HeaderMap`,
+/// `http::Response` and `reqwest::Response`.
+pub trait GraphHeadersExt: sealed::Sealed {
+    /// Get all _The Graph_ typed headers at once.
+    ///
+    /// Unlike `headers::HeaderMapExt::typed_get`, decoding failures are reported per header,
+    /// with the reason why the header value could not be decoded.
+    ///
+    /// # Example
+    ///
+    /// ```rust
+    /// use thegraph_headers::{GraphHeadersExt as _, graph_attestable::GraphAttestable};
+    ///
+    /// let response = http::Response::builder()
+    ///     .header("graph-attestable", "true")
+    ///     .header("graph-indexed", "invalid")
+    ///     .body(())
+    ///     .expect("failed to build response");
+    ///
+    /// let headers = response.graph_headers();
+    /// assert!(matches!(headers.attestable, Ok(Some(GraphAttestable(true)))));
+    /// assert!(headers.indexed.is_err());
+    /// ```
+    fn graph_headers(&self) -> GraphHeaders;
+}
+
+impl GraphHeadersExt for http::HeaderMap {
+    #[inline]
+    fn graph_headers(&self) -> GraphHeaders {
+        GraphHeaders::from_headers(self)
+    }
+}
+
+impl<B> GraphHeadersExt for http::Response<B> {
+    #[inline]
+    fn graph_headers(&self) -> GraphHeaders {
+        GraphHeaders::from_headers(self.headers())
+    }
+}
+
+#[cfg(feature = "reqwest")]
+impl GraphHeadersExt for reqwest::Response {
+    #[inline]
+    fn graph_headers(&self) -> GraphHeaders {
+        GraphHeaders::from_headers(self.headers())
+    }
+}
+
+/// Sealed trait to prevent downstream implementations of `GraphHeadersExt`.
+mod sealed {
+    pub trait Sealed {}
+    impl Sealed for http::HeaderMap {}
+    impl<B> Sealed for http::Response<B> {}
+    #[cfg(feature = "reqwest")]
+    impl Sealed for reqwest::Response {}
+}
+
+#[cfg(test)]
+mod tests {
+    use headers::HeaderMapExt as _;
+    use thegraph_core::alloy::primitives::BlockHash;
+
+    use super::GraphHeadersExt as _;
+    use crate::{
+        DecodeError,
+        graph_indexed::{BlockInfo, GraphIndexed},
+    };
+
+    #[test]
+    fn extract_valid_and_missing_headers() {
+        //* Given
+        let mut headers = http::HeaderMap::new();
+        headers.typed_insert(GraphIndexed(BlockInfo {
+            hash: BlockHash::new([0x55; 32]),
+            number: 42,
+            timestamp: None,
+        }));
+
+        //* When
+        let graph_headers = headers.graph_headers();
+
+        //* Then
+        assert!(matches!(
+            graph_headers.indexed,
+            Ok(Some(GraphIndexed(BlockInfo { number: 42, .. })))
+        ));
+        assert!(matches!(graph_headers.attestable, Ok(None)));
+    }
+
+    #[test]
+    fn report_detailed_errors_per_header() {
+        //* Given
+        let response = http::Response::builder()
+            .header(crate::graph_indexed::HEADER_NAME, r#"{"number":42}"#)
+            .header(crate::graph_attestable::HEADER_NAME, "yes")
+            .body(())
+            .unwrap();
+
+        //* When
+        let graph_headers = response.graph_headers();
+
+        //* Then
+        let indexed = graph_headers.indexed.expect_err("header to be invalid");
+        assert_eq!(indexed.name, crate::graph_indexed::HEADER_NAME);
+        assert!(matches!(indexed.source, DecodeError::Json(_)));
+        assert!(indexed.to_string().contains("missing field `hash`"));
+
+        let attestable = graph_headers.attestable.expect_err("header to be invalid");
+        assert_eq!(
+            attestable.to_string(),
+            "invalid `graph-attestable` header: unexpected value, expected `true` or `false`"
+        );
+    }
+
+    #[cfg(feature = "attestation")]
+    #[test]
+    fn report_invalid_attestation_length() {
+        //* Given
+        let response = http::Response::builder()
+            .header(crate::graph_attestation::HEADER_NAME, "AAAA")
+            .header(crate::graph_attestable::HEADER_NAME, "true")
+            .body(())
+            .unwrap();
+
+        //* When
+        let graph_headers = response.graph_headers();
+
+        //* Then
+        assert!(matches!(
+            graph_headers.attestable,
+            Ok(Some(crate::graph_attestable::GraphAttestable(true)))
+        ));
+        let attestation = graph_headers.attestation.expect_err("header to be invalid");
+        assert!(matches!(
+            attestation.source,
+            DecodeError::InvalidLength {
+                expected: 161,
+                actual: 3
+            }
+        ));
+    }
+}
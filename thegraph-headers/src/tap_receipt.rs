@@ -0,0 +1,476 @@
+//! An HTTP _typed header_ for the `tap-receipt` header.
+//!
+//! The `tap-receipt` header contains the signed Graph Tally receipt the payer (e.g., a gateway)
+//! attaches to every paid query. The header supports both the legacy (v1) allocation-keyed
+//! receipts and the _Horizon_ (v2) collection-keyed receipts, and two encodings:
+//!
+//! - [`ReceiptEncoding::Json`]: A JSON object with the `message` and `signature` fields.
+//! - [`ReceiptEncoding::Compact`]: The base64-encoded concatenation of a version byte, the
+//!   ABI-encoded receipt and the 65-byte signature.
+//!
+//! The receipt version and the encoding are auto-detected on decode.
+//!
+//! # Using the `headers::HeaderMapExt` extension trait
+//!
+//! ```rust
+//! # use thegraph_core::alloy::{primitives::address, signers::local::PrivateKeySigner};
+//! # use thegraph_core::{allocation_id, signed_message::sign, tally::v1};
+//! use headers::HeaderMapExt as _;
+//! use thegraph_headers::tap_receipt::{HEADER_NAME, SignedReceipt, TapReceipt};
+//!
+//! let mut header_map = http::HeaderMap::new();
+//! # let signer = PrivateKeySigner::random();
+//! # let domain = v1::eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+//! # let receipt = v1::Receipt {
+//! #     allocation_id: allocation_id!("3e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"),
+//! #     timestamp_ns: 1_700_000_000_000_000_000,
+//! #     nonce: 42,
+//! #     value: 1_000,
+//! # };
+//! # let value = SignedReceipt::V1(sign(&signer, &domain, receipt).unwrap());
+//!
+//! // Insert a `tap-receipt` HTTP header
+//! header_map.typed_insert(TapReceipt::compact(value));
+//!
+//! // Get the `tap-receipt` HTTP header by name
+//! let header_by_name = header_map.get(HEADER_NAME);
+//! assert!(header_by_name.is_some());
+//!
+//! // Get the `tap-receipt` HTTP header by type
+//! let header_typed = header_map.typed_get::<TapReceipt>();
+//! assert!(matches!(header_typed, Some(TapReceipt { receipt: SignedReceipt::V1(..), .. })));
+//! ```
+//!
+//! # Using the `HttpBuilderExt` extension trait
+//!
+//! ```rust
+//! # use thegraph_core::alloy::{primitives::address, signers::local::PrivateKeySigner};
+//! # use thegraph_core::{collection_id, signed_message::sign, tally};
+//! use thegraph_headers::{
+//!     HttpBuilderExt as _,
+//!     tap_receipt::{HEADER_NAME, SignedReceipt, TapReceipt},
+//! };
+//!
+//! # let signer = PrivateKeySigner::random();
+//! # let domain = tally::eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+//! # let receipt = tally::Receipt {
+//! #     collection_id: collection_id!("0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"),
+//! #     payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+//! #     data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+//! #     service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+//! #     timestamp_ns: 1_700_000_000_000_000_000,
+//! #     nonce: 42,
+//! #     value: 1_000,
+//! # };
+//! # let value = SignedReceipt::V2(sign(&signer, &domain, receipt).unwrap());
+//! let request = http::request::Builder::new()
+//!     .header_typed(TapReceipt::json(value))
+//!     .body(())
+//!     .expect("failed to build request");
+//!
+//! assert!(request.headers().get(HEADER_NAME).is_some());
+//! ```
+
+use base64::{Engine as _, prelude::BASE64_STANDARD};
+use headers::{Error as HeaderError, Header, HeaderName, HeaderValue};
+use thegraph_core::{
+    CollectionId,
+    alloy::{primitives::Signature, sol_types::SolValue},
+    signed_message::{SignedMessage, ToSolStruct},
+    tally::{self, v1},
+};
+
+use crate::decode;
+
+/// The HTTP header name for the `tap-receipt` header.
+pub const HEADER_NAME: &str = "tap-receipt";
+
+/// The compact encoding version byte of the legacy (v1) receipts
+const COMPACT_V1: u8 = 1;
+
+/// The compact encoding version byte of the _Horizon_ (v2) receipts
+const COMPACT_V2: u8 = 2;
+
+/// The ECDSA signature length, in bytes
+const SIGNATURE_LEN: usize = 65;
+
+/// A versioned signed Graph Tally receipt.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignedReceipt {
+    /// A legacy (v1) allocation-keyed receipt
+    V1(v1::SignedReceipt),
+    /// A _Horizon_ (v2) collection-keyed receipt
+    V2(tally::SignedReceipt),
+}
+
+/// The `tap-receipt` header value encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum ReceiptEncoding {
+    /// JSON-encoded receipt
+    #[default]
+    Json,
+    /// Base64-encoded packed receipt
+    Compact,
+}
+
+/// An HTTP _typed header_ for the `tap-receipt` header.
+///
+/// The `tap-receipt` header contains a signed Graph Tally receipt, either JSON or base64 compact
+/// encoded. When decoding, the encoding is set to the one used by the header value.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TapReceipt {
+    /// The signed receipt
+    pub receipt: SignedReceipt,
+    /// The header value encoding
+    pub encoding: ReceiptEncoding,
+}
+
+impl TapReceipt {
+    /// Create a new `tap-receipt` header, JSON encoded.
+    pub fn json(receipt: SignedReceipt) -> Self {
+        Self {
+            receipt,
+            encoding: ReceiptEncoding::Json,
+        }
+    }
+
+    /// Create a new `tap-receipt` header, base64 compact encoded.
+    pub fn compact(receipt: SignedReceipt) -> Self {
+        Self {
+            receipt,
+            encoding: ReceiptEncoding::Compact,
+        }
+    }
+}
+
+impl Header for TapReceipt {
+    fn name() -> &'static HeaderName {
+        static HTTP_HEADER_NAME: HeaderName = HeaderName::from_static(HEADER_NAME);
+        &HTTP_HEADER_NAME
+    }
+
+    fn decode<'i, I>(values: &mut I) -> Result<Self, HeaderError>
+    where
+        Self: Sized,
+        I: Iterator<Item = &'i HeaderValue>,
+    {
+        let value = values.next().ok_or_else(HeaderError::invalid)?;
+        let bytes = value.as_bytes();
+
+        if decode::is_json_object(bytes) {
+            let receipt = decode_json(bytes).map_err(|_| HeaderError::invalid())?;
+            Ok(Self::json(receipt))
+        } else {
+            let receipt = decode_compact(bytes).ok_or_else(HeaderError::invalid)?;
+            Ok(Self::compact(receipt))
+        }
+    }
+
+    fn encode<E: Extend<HeaderValue>>(&self, values: &mut E) {
+        let bytes = match self.encoding {
+            ReceiptEncoding::Json => encode_json(&self.receipt),
+            ReceiptEncoding::Compact => encode_compact(&self.receipt).into_bytes(),
+        };
+        let value = HeaderValue::from_bytes(&bytes).expect("header to be valid utf-8");
+        values.extend(std::iter::once(value));
+    }
+}
+
+/// Encode the signed receipt using the base64 compact encoding.
+fn encode_compact(receipt: &SignedReceipt) -> String {
+    let (version, message, signature) = match receipt {
+        SignedReceipt::V1(receipt) => (
+            COMPACT_V1,
+            receipt.message.to_sol_struct().abi_encode(),
+            &receipt.signature,
+        ),
+        SignedReceipt::V2(receipt) => (
+            COMPACT_V2,
+            receipt.message.to_sol_struct().abi_encode(),
+            &receipt.signature,
+        ),
+    };
+
+    let mut buf = Vec::with_capacity(1 + message.len() + SIGNATURE_LEN);
+    buf.push(version);
+    buf.extend_from_slice(&message);
+    buf.extend_from_slice(&signature.as_bytes());
+    BASE64_STANDARD.encode(buf)
+}
+
+/// Decode a base64 compact encoded signed receipt.
+fn decode_compact(value: &[u8]) -> Option<SignedReceipt> {
+    let buf = BASE64_STANDARD.decode(value).ok()?;
+    let (version, buf) = buf.split_first()?;
+    let (message, signature) = buf.split_at_checked(buf.len().checked_sub(SIGNATURE_LEN)?)?;
+    let signature = Signature::from_raw(signature).ok()?;
+
+    match *version {
+        COMPACT_V1 => {
+            let receipt = v1::sol::Receipt::abi_decode(message).ok()?;
+            Some(SignedReceipt::V1(SignedMessage {
+                message: v1::Receipt {
+                    allocation_id: receipt.allocation_id.into(),
+                    timestamp_ns: receipt.timestamp_ns,
+                    nonce: receipt.nonce,
+                    value: receipt.value,
+                },
+                signature,
+            }))
+        }
+        COMPACT_V2 => {
+            let receipt = tally::sol::Receipt::abi_decode(message).ok()?;
+            Some(SignedReceipt::V2(SignedMessage {
+                message: tally::Receipt {
+                    collection_id: CollectionId::new(receipt.collection_id),
+                    payer: receipt.payer,
+                    data_service: receipt.data_service,
+                    service_provider: receipt.service_provider,
+                    timestamp_ns: receipt.timestamp_ns,
+                    nonce: receipt.nonce,
+                    value: receipt.value,
+                },
+                signature,
+            }))
+        }
+        _ => None,
+    }
+}
+
+/// The JSON representation of a signed receipt.
+#[derive(serde::Serialize, serde::Deserialize)]
+struct SignedReceiptSerde<M> {
+    message: M,
+    signature: Signature,
+}
+
+/// Probe the JSON representation of a signed receipt to detect its version.
+///
+/// The v2 receipts have a `collection_id` field, while v1 receipts have an `allocation_id` field.
+#[derive(serde::Deserialize)]
+struct SignedReceiptVersionProbe {
+    message: ReceiptVersionProbe,
+}
+
+#[derive(serde::Deserialize)]
+struct ReceiptVersionProbe {
+    collection_id: Option<serde::de::IgnoredAny>,
+}
+
+/// Deserialize a JSON-encoded signed receipt, detecting its version.
+fn decode_json(value: &[u8]) -> Result<SignedReceipt, serde_json::Error> {
+    let probe = serde_json::from_slice::<'_, SignedReceiptVersionProbe>(value)?;
+    if probe.message.collection_id.is_some() {
+        let receipt = serde_json::from_slice::<'_, SignedReceiptSerde<tally::Receipt>>(value)?;
+        Ok(SignedReceipt::V2(SignedMessage {
+            message: receipt.message,
+            signature: receipt.signature,
+        }))
+    } else {
+        let receipt = serde_json::from_slice::<'_, SignedReceiptSerde<v1::Receipt>>(value)?;
+        Ok(SignedReceipt::V1(SignedMessage {
+            message: receipt.message,
+            signature: receipt.signature,
+        }))
+    }
+}
+
+/// Serialize a signed receipt as JSON.
+fn encode_json(receipt: &SignedReceipt) -> Vec<u8> {
+    match receipt {
+        SignedReceipt::V1(receipt) => serde_json::to_vec(&SignedReceiptSerde {
+            message: &receipt.message,
+            signature: receipt.signature,
+        }),
+        SignedReceipt::V2(receipt) => serde_json::to_vec(&SignedReceiptSerde {
+            message: &receipt.message,
+            signature: receipt.signature,
+        }),
+    }
+    .expect("header to be valid json")
+}
+
+#[cfg(test)]
+mod tests {
+    use base64::{Engine as _, prelude::BASE64_STANDARD};
+    use headers::{Header, HeaderValue};
+    use thegraph_core::{
+        allocation_id,
+        alloy::{primitives::address, signers::local::PrivateKeySigner},
+        collection_id,
+        signed_message::sign,
+        tally::{self, v1},
+    };
+
+    use super::{ReceiptEncoding, SignedReceipt, TapReceipt, decode_json, encode_json};
+
+    /// Create a signed legacy (v1) receipt for testing
+    fn receipt_v1() -> SignedReceipt {
+        let signer = PrivateKeySigner::random();
+        let domain = v1::eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+        let receipt = v1::Receipt {
+            allocation_id: allocation_id!("3e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"),
+            timestamp_ns: 1_700_000_000_000_000_000,
+            nonce: 42,
+            value: 1_000,
+        };
+        SignedReceipt::V1(sign(&signer, &domain, receipt).expect("failed to sign receipt"))
+    }
+
+    /// Create a signed _Horizon_ (v2) receipt for testing
+    fn receipt_v2() -> SignedReceipt {
+        let signer = PrivateKeySigner::random();
+        let domain = tally::eip712_domain(1, address!("a83682bbe91c0d2d48a13fd751b2da8e989fe421"));
+        let receipt = tally::Receipt {
+            collection_id: collection_id!(
+                "0000000000000000000000003e1f9c2ab4c7f1b3d7e839ebe6ae451c8a0b1d24"
+            ),
+            payer: address!("03f6d2a3d8c3413de72c193386f1894e1ddc2b6b"),
+            data_service: address!("16def7e0108a5467a106dbd7537f8591f470342e"),
+            service_provider: address!("90f8bf6a479f320ead074411a4b0e7944ea8c9c1"),
+            timestamp_ns: 1_700_000_000_000_000_000,
+            nonce: 42,
+            value: 1_000,
+        };
+        SignedReceipt::V2(sign(&signer, &domain, receipt).expect("failed to sign receipt"))
+    }
+
+    #[test]
+    fn encode_receipt_into_json_header() {
+        //* Given
+        let receipt = receipt_v2();
+
+        let mut headers = vec![];
+
+        //* When
+        let header = TapReceipt::json(receipt.clone());
+
+        header.encode(&mut headers);
+
+        //* Then
+        let value = headers.first().expect("header to have been encoded");
+
+        let json: serde_json::Value =
+            serde_json::from_slice(value.as_bytes()).expect("header to be valid json");
+        assert!(json["message"]["collection_id"].is_string());
+        assert!(json["signature"]["r"].is_string());
+
+        let decoded = decode_json(value.as_bytes()).expect("header to be a valid receipt");
+        assert_eq!(decoded, receipt);
+    }
+
+    #[test]
+    fn encode_receipt_into_compact_header() {
+        //* Given
+        let receipt = receipt_v2();
+
+        let mut headers = vec![];
+
+        //* When
+        let header = TapReceipt::compact(receipt);
+
+        header.encode(&mut headers);
+
+        //* Then
+        let value = headers.first().expect("header to have been encoded");
+
+        // Version byte, 7 ABI-encoded words and the 65-byte signature
+        let bytes = BASE64_STANDARD
+            .decode(value.as_bytes())
+            .expect("header to be valid base64");
+        assert_eq!(bytes.len(), 1 + 7 * 32 + 65);
+        assert_eq!(bytes[0], 2);
+    }
+
+    #[test]
+    fn decode_receipt_from_valid_json_headers() {
+        for receipt in [receipt_v1(), receipt_v2()] {
+            //* Given
+            let header = HeaderValue::from_bytes(&encode_json(&receipt)).unwrap();
+            let headers = [header];
+
+            //* When
+            let header = TapReceipt::decode(&mut headers.iter());
+
+            //* Then
+            let header = header.expect("header to be valid");
+            assert_eq!(header.receipt, receipt);
+            assert_eq!(header.encoding, ReceiptEncoding::Json);
+        }
+    }
+
+    #[test]
+    fn decode_receipt_from_valid_compact_headers() {
+        for receipt in [receipt_v1(), receipt_v2()] {
+            //* Given
+            let mut headers = vec![];
+            TapReceipt::compact(receipt.clone()).encode(&mut headers);
+
+            //* When
+            let header = TapReceipt::decode(&mut headers.iter());
+
+            //* Then
+            let header = header.expect("header to be valid");
+            assert_eq!(header.receipt, receipt);
+            assert_eq!(header.encoding, ReceiptEncoding::Compact);
+        }
+    }
+
+    #[test]
+    fn decode_receipt_from_first_header() {
+        //* Given
+        let mut headers = vec![];
+        TapReceipt::json(receipt_v1()).encode(&mut headers);
+        headers.push(HeaderValue::from_static("invalid"));
+        headers.push(HeaderValue::from_static(""));
+
+        //* When
+        let result = TapReceipt::decode(&mut headers.iter());
+
+        //* Then
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn fail_decode_receipt_from_unknown_compact_version() {
+        //* Given
+        let mut headers = vec![];
+        TapReceipt::compact(receipt_v1()).encode(&mut headers);
+
+        // Replace the version byte with an unknown version
+        let mut bytes = BASE64_STANDARD.decode(headers[0].as_bytes()).unwrap();
+        bytes[0] = 42;
+        let headers = [HeaderValue::from_str(&BASE64_STANDARD.encode(bytes)).unwrap()];
+
+        //* When
+        let result = TapReceipt::decode(&mut headers.iter());
+
+        //* Then
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn fail_decode_receipt_from_invalid_header() {
+        //* Given
+        let header = HeaderValue::from_static("invalid");
+        let headers = [header];
+
+        //* When
+        let header = TapReceipt::decode(&mut headers.iter());
+
+        //* Then
+        assert!(header.is_err());
+    }
+
+    #[test]
+    fn fail_decode_receipt_if_no_headers() {
+        //* Given
+        let headers = [];
+
+        //* When
+        let header = TapReceipt::decode(&mut headers.iter());
+
+        //* Then
+        assert!(header.is_err());
+    }
+}
@@ -0,0 +1,550 @@
+//! A _tower_ middleware that stamps _The Graph_ typed headers on responses.
+//!
+//! The [`AttestationLayer`] wraps a service handling GraphQL queries and inserts the following
+//! headers into its responses:
+//!
+//! - `graph-indexed`: If the inner service inserted a [`BlockInfo`] into the response extensions.
+//! - `graph-attestable`: Whether the response is attestable or not.
+//! - `graph-attestation`: The attestation of the request-response pair, signed with the signer
+//!   resolved for the queried deployment, or an empty value if the response is not attestable.
+//!
+//! The queried [`DeploymentId`] is read from the request extensions, e.g., inserted by the
+//! service's router, and the attestation signer is resolved via a [`SignerResolver`].
+//!
+//! A response is attestable if all the following conditions are met:
+//!
+//! - The response status code is a success status code.
+//! - The inner service did not insert a `GraphAttestable(false)` into the response extensions.
+//! - The request extensions contain the deployment ID, and a signer is resolved for it.
+//! - The request and response bodies are valid UTF-8.
+//!
+//! The request body of requests with a resolved signer, and the response body of attestable
+//! responses, are buffered in memory, as the attestation covers their full contents. Any other
+//! request and response body is passed through untouched. The buffered bodies size is limited,
+//! see [`AttestationLayer::with_body_limit`]. A response body known to exceed the limit, via its
+//! size hint or its `Content-Length` header, is passed through untouched and not attested.
+//!
+//! # Example
+//!
+//! ```rust
+//! use std::collections::HashMap;
+//!
+//! use thegraph_core::{
+//!     DeploymentId, alloy::signers::local::PrivateKeySigner, attestation, deployment_id,
+//! };
+//! use thegraph_headers::tower::AttestationLayer;
+//!
+//! let deployment = deployment_id!("QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz");
+//! let signers = HashMap::<DeploymentId, _>::from([(deployment, PrivateKeySigner::random())]);
+//!
+//! let domain = attestation::eip712_domain(1337, Default::default());
+//! let layer = AttestationLayer::new(domain, signers);
+//! ```
+
+use std::{
+    collections::HashMap,
+    future::Future,
+    hash::BuildHasher,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use headers::{ContentLength, HeaderMapExt as _};
+use http::{Request, Response};
+use http_body::Body;
+use http_body_util::{BodyExt as _, Limited};
+use thegraph_core::{
+    DeploymentId,
+    alloy::{signers::SignerSync, sol_types::Eip712Domain},
+    attestation,
+};
+use tower_layer::Layer;
+use tower_service::Service;
+
+use crate::{
+    graph_attestable::GraphAttestable,
+    graph_attestation::GraphAttestation,
+    graph_indexed::{BlockInfo, GraphIndexed},
+};
+
+/// A boxed error type returned by the [`AttestationService`].
+pub type BoxError = Box<dyn std::error::Error + Send + Sync>;
+
+/// The default maximum size, in bytes, of the buffered request and response bodies: 10 MiB.
+pub const DEFAULT_BODY_LIMIT: usize = 10 * 1024 * 1024;
+
+/// A lookup of the attestation signer of a deployment.
+///
+/// The attestation signer is the key of the indexer's allocation of the deployment.
+pub trait SignerResolver {
+    /// The attestation signer type
+    type Signer: SignerSync;
+
+    /// Get the attestation signer of the given deployment.
+    ///
+    /// Returns `None` if there is no signer for the deployment, i.e., responses are not attested.
+    fn resolve_signer(&self, deployment: &DeploymentId) -> Option<Self::Signer>;
+}
+
+impl<F, S> SignerResolver for F
+where
+    F: Fn(&DeploymentId) -> Option<S>,
+    S: SignerSync,
+{
+    type Signer = S;
+
+    fn resolve_signer(&self, deployment: &DeploymentId) -> Option<Self::Signer> {
+        self(deployment)
+    }
+}
+
+impl<S: SignerSync + Clone, H: BuildHasher> SignerResolver for HashMap<DeploymentId, S, H> {
+    type Signer = S;
+
+    fn resolve_signer(&self, deployment: &DeploymentId) -> Option<Self::Signer> {
+        self.get(deployment).cloned()
+    }
+}
+
+/// A [`Layer`] that stamps the `graph-indexed`, `graph-attestable` and `graph-attestation`
+/// headers on the inner service's responses.
+///
+/// See the [module documentation](self) for more information.
+#[derive(Debug)]
+pub struct AttestationLayer<R> {
+    domain: Arc<Eip712Domain>,
+    resolver: Arc<R>,
+    body_limit: usize,
+}
+
+impl<R> AttestationLayer<R> {
+    /// Create a new layer given the attestation EIP-712 domain and the signer resolver.
+    pub fn new(domain: Eip712Domain, resolver: R) -> Self {
+        Self {
+            domain: Arc::new(domain),
+            resolver: Arc::new(resolver),
+            body_limit: DEFAULT_BODY_LIMIT,
+        }
+    }
+
+    /// Set the maximum size, in bytes, of the buffered request and response bodies.
+    ///
+    /// If a buffered body exceeds the limit, the service fails with a
+    /// [`LengthLimitError`](http_body_util::LengthLimitError). A response body known upfront to
+    /// exceed the limit is not buffered, and the response is not attested instead. Defaults to
+    /// [`DEFAULT_BODY_LIMIT`].
+    pub fn with_body_limit(mut self, limit: usize) -> Self {
+        self.body_limit = limit;
+        self
+    }
+}
+
+impl<R> Clone for AttestationLayer<R> {
+    fn clone(&self) -> Self {
+        Self {
+            domain: Arc::clone(&self.domain),
+            resolver: Arc::clone(&self.resolver),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+impl<S, R> Layer<S> for AttestationLayer<R> {
+    type Service = AttestationService<S, R>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        AttestationService {
+            inner,
+            domain: Arc::clone(&self.domain),
+            resolver: Arc::clone(&self.resolver),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+/// The service created by the [`AttestationLayer`].
+#[derive(Debug)]
+pub struct AttestationService<S, R> {
+    inner: S,
+    domain: Arc<Eip712Domain>,
+    resolver: Arc<R>,
+    body_limit: usize,
+}
+
+impl<S: Clone, R> Clone for AttestationService<S, R> {
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+            domain: Arc::clone(&self.domain),
+            resolver: Arc::clone(&self.resolver),
+            body_limit: self.body_limit,
+        }
+    }
+}
+
+impl<S, R, ReqBody, ResBody> Service<Request<ReqBody>> for AttestationService<S, R>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Error: Into<BoxError>,
+    S::Future: Send,
+    R: SignerResolver + Send + Sync + 'static,
+    R::Signer: Send,
+    ReqBody: Body + From<Bytes> + Send + 'static,
+    ReqBody::Data: Send,
+    ReqBody::Error: Into<BoxError>,
+    ResBody: Body + From<Bytes> + Send + 'static,
+    ResBody::Data: Send,
+    ResBody::Error: Into<BoxError>,
+{
+    type Response = Response<ResBody>;
+    type Error = BoxError;
+    type Future = Pin<Box<dyn Future<Output = Result<Self::Response, Self::Error>> + Send>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        // Take the service that was driven to readiness, and leave a clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let domain = Arc::clone(&self.domain);
+        let resolver = Arc::clone(&self.resolver);
+        let body_limit = self.body_limit;
+
+        Box::pin(async move {
+            // Resolve the signer of the queried deployment, if any, upfront. Without a signer,
+            // the response is not attested, and the request body is passed through untouched.
+            let signer = req
+                .extensions()
+                .get::<DeploymentId>()
+                .and_then(|deployment| Some((*deployment, resolver.resolve_signer(deployment)?)));
+            let Some((deployment, signer)) = signer else {
+                let response = inner.call(req).await.map_err(Into::into)?;
+                return Ok(stamp_unattested(response));
+            };
+
+            // Buffer the request body, as the attestation covers the request contents
+            let (parts, body) = req.into_parts();
+            let request = Limited::new(body, body_limit).collect().await?.to_bytes();
+            let response = inner
+                .call(Request::from_parts(parts, ReqBody::from(request.clone())))
+                .await
+                .map_err(Into::into)?;
+
+            let attestable = response.status().is_success()
+                && !matches!(
+                    response.extensions().get::<GraphAttestable>(),
+                    Some(GraphAttestable(false))
+                );
+            if !attestable {
+                return Ok(stamp_unattested(response));
+            }
+
+            // A response body known to exceed the limit is not attested, instead of failing the
+            // already executed query. A body of unknown size is buffered up to the limit.
+            let (mut parts, body) = response.into_parts();
+            let body_size = body
+                .size_hint()
+                .upper()
+                .or_else(|| parts.headers.typed_get::<ContentLength>().map(|len| len.0));
+            if body_size.is_some_and(|size| size > body_limit as u64) {
+                return Ok(stamp_unattested(Response::from_parts(parts, body)));
+            }
+
+            // Buffer the response body, and sign the request-response pair. If the bodies are not
+            // valid UTF-8, or the signer fails, the response is not attested.
+            let response = Limited::new(body, body_limit).collect().await?.to_bytes();
+            let attestation = match (
+                std::str::from_utf8(&request),
+                std::str::from_utf8(&response),
+            ) {
+                (Ok(request), Ok(response)) => {
+                    attestation::try_create(&domain, &signer, &deployment, request, response).ok()
+                }
+                _ => None,
+            };
+
+            stamp_indexed(&mut parts);
+            parts
+                .headers
+                .typed_insert(GraphAttestable(attestation.is_some()));
+            parts.headers.typed_insert(GraphAttestation(attestation));
+            Ok(Response::from_parts(parts, ResBody::from(response)))
+        })
+    }
+}
+
+/// Stamp the `graph-indexed` header, if the inner service inserted a [`BlockInfo`] into the
+/// response extensions.
+fn stamp_indexed(parts: &mut http::response::Parts) {
+    if let Some(info) = parts.extensions.get::<BlockInfo>() {
+        parts.headers.typed_insert(GraphIndexed(info.clone()));
+    }
+}
+
+/// Stamp the headers of a response that is not attested, leaving the response body untouched.
+fn stamp_unattested<B>(response: Response<B>) -> Response<B> {
+    let (mut parts, body) = response.into_parts();
+    stamp_indexed(&mut parts);
+    parts.headers.typed_insert(GraphAttestable(false));
+    parts.headers.typed_insert(GraphAttestation(None));
+    Response::from_parts(parts, body)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::{collections::HashMap, convert::Infallible};
+
+    use bytes::Bytes;
+    use headers::HeaderMapExt as _;
+    use http::{Request, Response, StatusCode};
+    use http_body_util::{BodyExt as _, Full};
+    use thegraph_core::{
+        DeploymentId,
+        alloy::{
+            primitives::{B256, BlockHash, ChainId, Signature},
+            signers::{self, SignerSync, local::PrivateKeySigner},
+        },
+        attestation, deployment_id,
+    };
+    use tower::{Layer as _, ServiceExt as _, service_fn};
+
+    use super::{AttestationLayer, SignerResolver};
+    use crate::{
+        graph_attestable::GraphAttestable,
+        graph_attestation::GraphAttestation,
+        graph_indexed::{BlockInfo, GraphIndexed},
+    };
+
+    const DEPLOYMENT: DeploymentId =
+        deployment_id!("QmWmyoMoctfbAaiEs2G46gpeUmhqFRDW6KWo64y5r581Vz");
+
+    const REQUEST: &str = r#"{"query":"{ _meta { block { number } } }"}"#;
+    const RESPONSE: &str = r#"{"data":{"_meta":{"block":{"number":42}}}}"#;
+
+    /// A stand-in for a GraphQL query handling service.
+    ///
+    /// Responds with the given status code, and marks the response as not attestable if the
+    /// request contains the `x-not-attestable` header.
+    async fn graphql_service(
+        req: Request<Full<Bytes>>,
+    ) -> Result<Response<Full<Bytes>>, Infallible> {
+        let mut response = Response::new(Full::from(RESPONSE));
+        response.extensions_mut().insert(BlockInfo {
+            hash: BlockHash::new([0x55; 32]),
+            number: 42,
+            timestamp: None,
+        });
+        if req.headers().contains_key("x-not-attestable") {
+            response.extensions_mut().insert(GraphAttestable(false));
+        }
+        Ok(response)
+    }
+
+    /// A signer failing to sign any message, e.g., a remote signer that is unavailable.
+    #[derive(Clone)]
+    struct FailingSigner;
+
+    impl SignerSync for FailingSigner {
+        fn sign_hash_sync(&self, _hash: &B256) -> signers::Result<Signature> {
+            Err(signers::Error::other("signer unavailable"))
+        }
+
+        fn chain_id_sync(&self) -> Option<ChainId> {
+            None
+        }
+    }
+
+    /// Send a request with the given deployment ID to the layered stand-in service
+    async fn send<R>(
+        layer: &AttestationLayer<R>,
+        deployment: Option<DeploymentId>,
+        not_attestable: bool,
+    ) -> Response<Full<Bytes>>
+    where
+        R: SignerResolver + Send + Sync + 'static,
+        R::Signer: Send,
+    {
+        let mut request = Request::post("/").body(Full::from(REQUEST)).unwrap();
+        if let Some(deployment) = deployment {
+            request.extensions_mut().insert(deployment);
+        }
+        if not_attestable {
+            request
+                .headers_mut()
+                .insert("x-not-attestable", "true".parse().unwrap());
+        }
+
+        layer
+            .layer(service_fn(graphql_service))
+            .oneshot(request)
+            .await
+            .expect("service to respond")
+    }
+
+    #[tokio::test]
+    async fn stamp_attestation_on_attestable_responses() {
+        //* Given
+        let signer = PrivateKeySigner::random();
+        let domain = attestation::eip712_domain(1337, Default::default());
+        let layer = AttestationLayer::new(
+            domain.clone(),
+            HashMap::from([(DEPLOYMENT, signer.clone())]),
+        );
+
+        //* When
+        let response = send(&layer, Some(DEPLOYMENT), false).await;
+
+        //* Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(
+            response.headers().typed_get::<GraphIndexed>(),
+            Some(GraphIndexed(BlockInfo { number: 42, .. }))
+        ));
+        assert!(matches!(
+            response.headers().typed_get::<GraphAttestable>(),
+            Some(GraphAttestable(true))
+        ));
+
+        let Some(GraphAttestation(Some(att))) = response.headers().typed_get::<GraphAttestation>()
+        else {
+            panic!("response to be attested");
+        };
+        assert_eq!(
+            attestation::verify(&domain, &att, &signer.address(), REQUEST, RESPONSE),
+            Ok(())
+        );
+
+        // The response body is preserved
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, RESPONSE);
+    }
+
+    #[tokio::test]
+    async fn skip_attestation_of_non_attestable_responses() {
+        //* Given
+        let domain = attestation::eip712_domain(1337, Default::default());
+        let layer = AttestationLayer::new(
+            domain,
+            HashMap::from([(DEPLOYMENT, PrivateKeySigner::random())]),
+        );
+
+        //* When
+        let not_attestable = send(&layer, Some(DEPLOYMENT), true).await;
+        let unknown_deployment = send(&layer, None, false).await;
+
+        //* Then
+        for response in [not_attestable, unknown_deployment] {
+            assert!(matches!(
+                response.headers().typed_get::<GraphAttestable>(),
+                Some(GraphAttestable(false))
+            ));
+            assert!(matches!(
+                response.headers().typed_get::<GraphAttestation>(),
+                Some(GraphAttestation(None))
+            ));
+            assert!(response.headers().typed_get::<GraphIndexed>().is_some());
+        }
+    }
+
+    #[tokio::test]
+    async fn skip_attestation_if_signer_fails() {
+        //* Given
+        let domain = attestation::eip712_domain(1337, Default::default());
+        let layer = AttestationLayer::new(domain, HashMap::from([(DEPLOYMENT, FailingSigner)]));
+
+        //* When
+        let response = send(&layer, Some(DEPLOYMENT), false).await;
+
+        //* Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(
+            response.headers().typed_get::<GraphAttestation>(),
+            Some(GraphAttestation(None))
+        ));
+
+        // The response body is preserved
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, RESPONSE);
+    }
+
+    #[tokio::test]
+    async fn limit_only_the_buffered_bodies_size() {
+        //* Given
+        let domain = attestation::eip712_domain(1337, Default::default());
+        let layer = AttestationLayer::new(
+            domain,
+            HashMap::from([(DEPLOYMENT, PrivateKeySigner::random())]),
+        )
+        .with_body_limit(REQUEST.len() - 1);
+
+        //* When
+        let unknown_deployment = send(&layer, None, false).await;
+        let attested = layer
+            .layer(service_fn(graphql_service))
+            .oneshot({
+                let mut request = Request::post("/").body(Full::from(REQUEST)).unwrap();
+                request.extensions_mut().insert(DEPLOYMENT);
+                request
+            })
+            .await;
+
+        //* Then
+        // The request body is not buffered if the response is not attested
+        assert_eq!(unknown_deployment.status(), StatusCode::OK);
+        assert!(matches!(
+            unknown_deployment.headers().typed_get::<GraphAttestation>(),
+            Some(GraphAttestation(None))
+        ));
+
+        let err = attested.expect_err("request body to exceed the limit");
+        assert!(err.is::<http_body_util::LengthLimitError>());
+    }
+
+    #[tokio::test]
+    async fn skip_attestation_of_responses_exceeding_body_limit() {
+        //* Given
+        let domain = attestation::eip712_domain(1337, Default::default());
+        let layer = AttestationLayer::new(
+            domain,
+            HashMap::from([(DEPLOYMENT, PrivateKeySigner::random())]),
+        )
+        .with_body_limit(REQUEST.len());
+
+        let large_response = format!("{RESPONSE}{}", " ".repeat(REQUEST.len()));
+        let service = service_fn({
+            let large_response = large_response.clone();
+            move |_req: Request<Full<Bytes>>| {
+                let body = Full::<Bytes>::from(large_response.clone());
+                async move { Ok::<_, Infallible>(Response::new(body)) }
+            }
+        });
+
+        //* When
+        let mut request = Request::post("/").body(Full::from(REQUEST)).unwrap();
+        request.extensions_mut().insert(DEPLOYMENT);
+        let response = layer
+            .layer(service)
+            .oneshot(request)
+            .await
+            .expect("service to respond");
+
+        //* Then
+        assert_eq!(response.status(), StatusCode::OK);
+        assert!(matches!(
+            response.headers().typed_get::<GraphAttestable>(),
+            Some(GraphAttestable(false))
+        ));
+        assert!(matches!(
+            response.headers().typed_get::<GraphAttestation>(),
+            Some(GraphAttestation(None))
+        ));
+
+        // The response body is preserved
+        let body = response.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(body, large_response);
+    }
+}